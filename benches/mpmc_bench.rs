@@ -1,5 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
-use mpmc_std::MpmcQueue;
+use mpmc_std::{set_wake_priority, Consumer, MpmcQueue, PrefetchConsumer, WakePolicy};
 use std::sync::Arc;
 use std::thread;
 use std::time::Instant;
@@ -94,6 +94,64 @@ fn multi_producer_single_consumer(c: &mut Criterion) {
     group.finish();
 }
 
+fn multi_producer_exclusive_consumer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("multi_producer_single_consumer");
+    
+    // Same workload as `multi_producer_single_consumer`, but the consumer
+    // skips the tail CAS via `ExclusiveConsumer`
+    for num_producers in [1, 2, 4, 8].iter() {
+        group.bench_with_input(
+            BenchmarkId::new("exclusive_consumer", num_producers), 
+            num_producers, 
+            |b, &num_producers| {
+                b.iter_custom(|iters| {
+                    let (producer, mut consumer) = MpmcQueue::with_exclusive_consumer(1024);
+                    let items_per_producer = (iters as usize) / num_producers;
+                    
+                    let start = Instant::now();
+                    
+                    let mut producer_handles = Vec::new();
+                    
+                    // Spawn producers
+                    for producer_id in 0..num_producers {
+                        let producer = producer.clone();
+                        let handle = thread::spawn(move || {
+                            for i in 0..items_per_producer {
+                                let value = producer_id * items_per_producer + i;
+                                while producer.send(black_box(value)).is_err() {
+                                    thread::yield_now();
+                                }
+                            }
+                        });
+                        producer_handles.push(handle);
+                    }
+                    drop(producer);
+                    
+                    // Consumer
+                    let consumer_handle = thread::spawn(move || {
+                        let total_items = items_per_producer * num_producers;
+                        for _ in 0..total_items {
+                            while consumer.recv().is_none() {
+                                thread::yield_now();
+                            }
+                        }
+                    });
+                    
+                    // Wait for completion
+                    for handle in producer_handles {
+                        handle.join().unwrap();
+                    }
+                    consumer_handle.join().unwrap();
+                    
+                    start.elapsed()
+                });
+            }
+        );
+    }
+    
+    group.finish();
+}
+
 fn single_producer_multi_consumer(c: &mut Criterion) {
     let mut group = c.benchmark_group("single_producer_multi_consumer");
     
@@ -235,6 +293,15 @@ fn latency_measurement(c: &mut Criterion) {
         });
     });
     
+    group.bench_function("spsc_send_latency", |b| {
+        let (mut producer, mut consumer) = MpmcQueue::spsc(1024);
+        
+        b.iter(|| {
+            producer.send(black_box(42)).unwrap();
+            black_box(consumer.recv().unwrap());
+        });
+    });
+    
     group.bench_function("recv_latency", |b| {
         let queue = Arc::new(MpmcQueue::new(1024));
         // Pre-fill queue
@@ -308,13 +375,115 @@ fn contention_benchmark(c: &mut Criterion) {
     group.finish();
 }
 
+fn prefetch_consumer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("prefetch_consumer");
+    
+    // One producer keeps the queue fed while the consumer does a little
+    // work per item, which is the time the prefetch has to land
+    fn run<C>(
+        iters: u64,
+        consumer: impl FnOnce(Arc<MpmcQueue<usize>>) -> C,
+        recv: impl Fn(&C) -> Option<usize>,
+    ) -> std::time::Duration {
+        let queue = Arc::new(MpmcQueue::new(1024));
+        let producer_queue = Arc::clone(&queue);
+        let consumer = consumer(queue);
+        let producer = thread::spawn(move || {
+            for i in 0..iters as usize {
+                while producer_queue.send(black_box(i)).is_err() {
+                    thread::yield_now();
+                }
+            }
+        });
+        
+        let start = Instant::now();
+        for _ in 0..iters {
+            let item = loop {
+                match recv(&consumer) {
+                    Some(item) => break item,
+                    None => thread::yield_now(),
+                }
+            };
+            let mut work = item;
+            for _ in 0..16 {
+                work = black_box(work.wrapping_mul(31).wrapping_add(7));
+            }
+        }
+        let elapsed = start.elapsed();
+        producer.join().unwrap();
+        elapsed
+    }
+    
+    group.bench_function("plain", |b| {
+        b.iter_custom(|iters| run(iters, Consumer::new, Consumer::recv));
+    });
+    
+    group.bench_function("prefetch", |b| {
+        b.iter_custom(|iters| run(iters, PrefetchConsumer::new, PrefetchConsumer::recv));
+    });
+    
+    group.finish();
+}
+
+fn wakeup_latency(c: &mut Criterion) {
+    let mut group = c.benchmark_group("wakeup_latency");
+    
+    // Round trip from a send to a parked consumer and back through an
+    // acknowledgment queue, with several consumers parked in between
+    fn run(iters: u64, policy: WakePolicy) -> std::time::Duration {
+        const STOP: usize = usize::MAX;
+        let queue = Arc::new(MpmcQueue::with_wake_policy(64, policy));
+        let acks = Arc::new(MpmcQueue::new(64));
+        let consumers: Vec<_> = (0..4u8)
+            .map(|priority| {
+                let (queue, acks) = (Arc::clone(&queue), Arc::clone(&acks));
+                thread::spawn(move || {
+                    set_wake_priority(priority);
+                    loop {
+                        let item = queue.recv_blocking();
+                        if item == STOP {
+                            break;
+                        }
+                        acks.send(item).unwrap();
+                    }
+                })
+            })
+            .collect();
+        
+        let start = Instant::now();
+        for i in 0..iters as usize {
+            queue.send(black_box(i)).unwrap();
+            black_box(acks.recv_blocking());
+        }
+        let elapsed = start.elapsed();
+        for _ in 0..consumers.len() {
+            queue.send_blocking(STOP);
+        }
+        for consumer in consumers {
+            consumer.join().unwrap();
+        }
+        elapsed
+    }
+    
+    for policy in [WakePolicy::Fifo, WakePolicy::Lifo, WakePolicy::Priority] {
+        group.bench_function(format!("{policy:?}").to_lowercase(), |b| {
+            b.iter_custom(|iters| run(iters, policy));
+        });
+    }
+    
+    group.finish();
+}
+
 criterion_group!(
     benches,
     single_threaded_throughput,
     multi_producer_single_consumer,
+    multi_producer_exclusive_consumer,
     single_producer_multi_consumer,
     multi_producer_multi_consumer,
     latency_measurement,
+    prefetch_consumer,
+    wakeup_latency,
     contention_benchmark
 );
 criterion_main!(benches);
\ No newline at end of file
@@ -1,7 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion, BenchmarkId};
 
 #[cfg(feature = "simd")]
-use mpmc_std::simd_queue::{SimdMpmcQueue, SimdProducer, SimdConsumer};
+use mpmc_std::simd_queue::{SimdElement, SimdMpmcQueue};
 
 use mpmc_std::MpmcQueue;
 use std::sync::Arc;
@@ -55,16 +55,16 @@ fn simd_single_threaded_throughput(c: &mut Criterion) {
             
             b.iter(|| {
                 for i in 0..total_items {
-                    while queue.send(black_box(i as u64)).is_err() {
-                        queue.recv();
+                    while queue.send_one(black_box(i as u64)).is_err() {
+                        queue.recv_one();
                     }
                     if i % 2 == 0 {
-                        black_box(queue.recv());
+                        black_box(queue.recv_one());
                     }
                 }
                 
                 // Drain remaining items
-                while queue.recv().is_some() {}
+                while queue.recv_one().is_some() {}
             });
         });
         
@@ -170,7 +170,7 @@ fn simd_multi_producer_consumer(c: &mut Criterion) {
                         handles.push(thread::spawn(move || {
                             for i in 0..items_per_thread {
                                 let value = (producer_id * items_per_thread + i) as u64;
-                                while queue_clone.send(value).is_err() {
+                                while queue_clone.send_one(value).is_err() {
                                     thread::yield_now();
                                 }
                             }
@@ -182,7 +182,7 @@ fn simd_multi_producer_consumer(c: &mut Criterion) {
                         let queue_clone = Arc::clone(&queue);
                         handles.push(thread::spawn(move || {
                             for _ in 0..items_per_thread {
-                                while queue_clone.recv().is_none() {
+                                while queue_clone.recv_one().is_none() {
                                     thread::yield_now();
                                 }
                             }
@@ -234,7 +234,7 @@ fn simd_batch_sizes(c: &mut Criterion) {
                         } else {
                             // Fall back to individual sends for large batches
                             for &item in &send_batch {
-                                while queue.send(item).is_err() {
+                                while queue.send_one(item).is_err() {
                                     thread::yield_now();
                                 }
                             }
@@ -250,7 +250,7 @@ fn simd_batch_sizes(c: &mut Criterion) {
                         } else {
                             for item in &mut recv_batch {
                                 loop {
-                                    match queue.recv() {
+                                    match queue.recv_one() {
                                         Some(value) => {
                                             *item = value;
                                             break;
@@ -271,6 +271,40 @@ fn simd_batch_sizes(c: &mut Criterion) {
     group.finish();
 }
 
+#[cfg(feature = "simd")]
+fn simd_mixed_batch_sizes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("simd_mixed_batch_sizes");
+    
+    // Uneven sends and receives keep the queue partly full, so claims have
+    // to fit whatever room is left rather than a whole batch
+    for fill in [0usize, 48, 60].iter() {
+        group.bench_with_input(BenchmarkId::new("prefilled", fill), fill, |b, &fill| {
+            let queue = SimdMpmcQueue::<u64>::new(64);
+            let prefill: Vec<u64> = (0..fill as u64).collect();
+            queue.send(&prefill).unwrap();
+            let items: Vec<u64> = (0..16).collect();
+            let mut buffer = vec![0u64; 16];
+            
+            b.iter(|| {
+                for (send_len, recv_len) in [(1, 3), (3, 2), (7, 5), (2, 4), (5, 4)] {
+                    let mut sent = 0;
+                    while sent < send_len {
+                        sent += match queue.send(&items[sent..send_len]) {
+                            Ok(n) | Err(n) => n,
+                        };
+                        if sent < send_len {
+                            queue.recv(&mut buffer);
+                        }
+                    }
+                    black_box(queue.recv(&mut buffer[..recv_len]));
+                }
+            });
+        });
+    }
+    
+    group.finish();
+}
+
 #[cfg(feature = "simd")]
 fn simd_latency_measurement(c: &mut Criterion) {
     let mut group = c.benchmark_group("simd_latency");
@@ -286,12 +320,30 @@ fn simd_latency_measurement(c: &mut Criterion) {
         });
     });
     
+    // The same batches copied slot by slot: the element is not vectorized,
+    // so only the batch claim is shared with the gather/scatter path
+    group.bench_function("scalar_copy_batch_latency", |b| {
+        #[derive(Clone, Copy)]
+        struct Lane(u64);
+        impl SimdElement for Lane {}
+        
+        let queue = Arc::new(SimdMpmcQueue::<Lane>::new(1024));
+        let batch = vec![Lane(42); 4];
+        let mut recv_buffer = vec![Lane(0); 4];
+        
+        b.iter(|| {
+            queue.send_batch(&batch).unwrap();
+            black_box(queue.recv_batch(&mut recv_buffer));
+            black_box(recv_buffer[0].0);
+        });
+    });
+    
     group.bench_function("simd_single_latency", |b| {
         let queue = Arc::new(SimdMpmcQueue::<u64>::new(1024));
         
         b.iter(|| {
-            queue.send(black_box(42u64)).unwrap();
-            black_box(queue.recv().unwrap());
+            queue.send_one(black_box(42u64)).unwrap();
+            black_box(queue.recv_one().unwrap());
         });
     });
     
@@ -307,6 +359,42 @@ fn simd_latency_measurement(c: &mut Criterion) {
     group.finish();
 }
 
+#[cfg(feature = "simd")]
+fn simd_aligned_loads(c: &mut Criterion) {
+    use mpmc_std::aligned::{Aligned, A32};
+
+    let mut group = c.benchmark_group("simd_aligned_loads");
+
+    // Batches of LANES through the gather/scatter path, with each slot's
+    // lane at its natural 8-byte offset after the sequence number. The lanes
+    // are copied one by one either way, so this measures what the padding
+    // of an over-aligned lane costs, not an aligned-load speedup
+    group.bench_function("unaligned_payload", |b| {
+        let queue = SimdMpmcQueue::<u64>::new(1024);
+        let items = [1u64, 2, 3, 4];
+        let mut out = [0u64; 4];
+
+        b.iter(|| {
+            queue.send(black_box(&items)).unwrap();
+            black_box(queue.recv(&mut out));
+        });
+    });
+
+    // The same batches with every slot's lane padded out to a 32-byte offset
+    group.bench_function("aligned_payload", |b| {
+        let queue = SimdMpmcQueue::<Aligned<u64, A32>>::new(1024);
+        let items = [1u64, 2, 3, 4].map(Aligned::new);
+        let mut out = [Aligned::new(0u64); 4];
+
+        b.iter(|| {
+            queue.send(black_box(&items)).unwrap();
+            black_box(queue.recv(&mut out));
+        });
+    });
+
+    group.finish();
+}
+
 // Fallback benchmarks when SIMD is not enabled
 #[cfg(not(feature = "simd"))]
 fn simd_disabled_placeholder(c: &mut Criterion) {
@@ -328,7 +416,9 @@ criterion_group!(
     simd_single_threaded_throughput,
     simd_multi_producer_consumer,
     simd_batch_sizes,
-    simd_latency_measurement
+    simd_mixed_batch_sizes,
+    simd_latency_measurement,
+    simd_aligned_loads
 );
 
 #[cfg(not(feature = "simd"))]
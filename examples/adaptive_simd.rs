@@ -18,7 +18,7 @@ fn main() {
         let data = vec![10u64, 20u64, 30u64, 40u64, 50u64, 60u64, 70u64];
         match producer.send(&data) {
             Ok(sent) => println!("   ✅ Sent {} items adaptively", sent),
-            Err(unsent) => println!("   ⚠️  Sent {} items, {} remaining", data.len() - unsent.len(), unsent.len()),
+            Err(sent) => println!("   ⚠️  Sent {} items, {} remaining", sent, data.len() - sent),
         }
         
         println!("   Queue length after send: {}", queue.len());
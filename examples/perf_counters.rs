@@ -0,0 +1,74 @@
+use mpmc_std::MpmcQueue;
+use mpmc_std::perf::{PerfCounters, PerfReport};
+use std::sync::Arc;
+use std::thread;
+
+const ITEMS_PER_THREAD: usize = 200_000;
+
+fn run_contention(queue_size: usize, thread_pairs: usize) {
+    let queue = Arc::new(MpmcQueue::new(queue_size));
+    let mut handles = Vec::new();
+
+    for p in 0..thread_pairs {
+        let queue_clone = Arc::clone(&queue);
+        handles.push(thread::spawn(move || {
+            for i in 0..ITEMS_PER_THREAD {
+                while queue_clone.send(p * ITEMS_PER_THREAD + i).is_err() {
+                    std::hint::spin_loop();
+                }
+            }
+        }));
+    }
+
+    for _ in 0..thread_pairs {
+        let queue_clone = Arc::clone(&queue);
+        handles.push(thread::spawn(move || {
+            for _ in 0..ITEMS_PER_THREAD {
+                while queue_clone.recv().is_none() {
+                    std::hint::spin_loop();
+                }
+            }
+        }));
+    }
+
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
+
+fn print_report(label: &str, report: &PerfReport, ops: usize) {
+    let per_op = |value: Option<u64>| match value {
+        Some(v) => format!("{:>12} ({:.2}/op)", v, v as f64 / ops as f64),
+        None => format!("{:>12}", "n/a"),
+    };
+
+    println!("{label}");
+    println!("   Cycles:                  {}", per_op(report.cycles));
+    println!("   Cache misses:            {}", per_op(report.cache_misses));
+    println!("   Stalled cycles (front):  {}", per_op(report.stalled_cycles_frontend));
+    println!("   Stalled cycles (back):   {}", per_op(report.stalled_cycles_backend));
+}
+
+fn main() {
+    println!("🔬 Queue Hardware Counter Report");
+    println!("================================");
+
+    let mut counters = match PerfCounters::new() {
+        Ok(counters) => counters,
+        Err(e) => {
+            println!("Hardware counters unavailable: {e}");
+            println!("Check /proc/sys/kernel/perf_event_paranoid (needs <= 2) or run with CAP_PERFMON.");
+            return;
+        }
+    };
+
+    for (queue_size, thread_pairs) in [(1024, 1), (1024, 4), (16, 4), (16, 8)] {
+        let ops = ITEMS_PER_THREAD * thread_pairs * 2;
+        let ((), report) = counters.measure(|| run_contention(queue_size, thread_pairs));
+        print_report(
+            &format!("\nQueue size {queue_size}, {thread_pairs} producer/consumer pairs"),
+            &report,
+            ops,
+        );
+    }
+}
@@ -107,9 +107,11 @@ fn run_simd_benchmarks() {
             
             let handle = thread::spawn(move || {
                 // Producer
-                let data_batch = vec![thread_id as u64; 7];
+                let data_batch = [thread_id as u64; 7];
                 for _ in 0..iterations_per_thread / 7 {
-                    while producer.send(&data_batch).is_err() {
+                    let mut rest = &data_batch[..];
+                    while let Err(sent) = producer.send(rest) {
+                        rest = &rest[sent..];
                         thread::yield_now();
                     }
                 }
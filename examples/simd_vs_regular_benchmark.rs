@@ -39,8 +39,10 @@ fn run_comprehensive_benchmark() {
     
     let start = Instant::now();
     for i in 0..iterations {
-        let batch = vec![(i*4) as u64, (i*4+1) as u64, (i*4+2) as u64, (i*4+3) as u64];
-        while simd_producer.send(&batch).is_err() {
+        let batch = [(i*4) as u64, (i*4+1) as u64, (i*4+2) as u64, (i*4+3) as u64];
+        let mut rest = &batch[..];
+        while let Err(sent) = simd_producer.send(rest) {
+            rest = &rest[sent..];
             let mut recv_buffer = vec![0u64; 4];
             simd_consumer.recv(&mut recv_buffer);
         }
@@ -95,7 +97,9 @@ fn run_comprehensive_benchmark() {
     for i in 0..iterations {
         let batch_size = (i % 7) + 1;
         let batch: Vec<u64> = (0..batch_size).map(|j| (i * 10 + j) as u64).collect();
-        while simd_producer.send(&batch).is_err() {
+        let mut rest = &batch[..];
+        while let Err(sent) = simd_producer.send(rest) {
+            rest = &rest[sent..];
             let mut recv_buffer = vec![0u64; 10];
             simd_consumer.recv(&mut recv_buffer);
         }
@@ -153,8 +157,10 @@ fn run_comprehensive_benchmark() {
         let producer = SimdProducer::new(Arc::clone(&simd_queue));
         handles.push(thread::spawn(move || {
             for i in 0..iterations_per_thread {
-                let batch = vec![(thread_id * 100000 + i) as u64; 4];
-                while producer.send(&batch).is_err() {
+                let batch = [(thread_id * 100000 + i) as u64; 4];
+                let mut rest = &batch[..];
+                while let Err(sent) = producer.send(rest) {
+                    rest = &rest[sent..];
                     thread::yield_now();
                 }
             }
@@ -0,0 +1,94 @@
+//! Over-aligned payload wrapper for queue slots.
+//!
+//! Each slot is padded to a cache line, but the data inside it only gets
+//! `T`'s natural alignment: in a 64-byte slot the payload starts right after
+//! the 8-byte sequence number. Wrapping the element type in `Aligned<T, A>`
+//! moves the payload to an `A`-aligned offset, so a consumer that reads it in
+//! place, e.g. through `ExclusiveConsumer::recv_ref`, can use aligned vector
+//! loads (`_mm256_load_si256` rather than `loadu`) on it.
+//!
+//! `SimdMpmcQueue` accepts `Aligned` of a `Simd64Bit` type as well, but its
+//! batch path copies one `u64` lane per cache-line slot, so an over-aligned
+//! lane does not become an aligned vector load there: it only adds padding.
+//! The `simd_aligned_loads` bench measures that cost; it shows no speedup.
+//!
+//! Memory cost: the sequence number still sits at the start of the slot, so
+//! the payload is pushed to the next `A` boundary. With `A32` and a payload of
+//! up to 32 bytes the slot stays at 64 bytes. With `A64`, or when the padded
+//! payload no longer fits in the first cache line, every slot grows by a
+//! whole extra cache line.
+
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+
+/// Marker type selecting the alignment of an `Aligned` payload.
+pub trait Alignment: Copy + Default + Send + Sync + 'static {}
+
+/// 16-byte alignment (SSE).
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[repr(align(16))]
+pub struct A16;
+
+/// 32-byte alignment (AVX/AVX2).
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[repr(align(32))]
+pub struct A32;
+
+/// 64-byte alignment (AVX-512, full cache line).
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+#[repr(align(64))]
+pub struct A64;
+
+impl Alignment for A16 {}
+impl Alignment for A32 {}
+impl Alignment for A64 {}
+
+/// A value over-aligned to the alignment of `A`.
+///
+/// Use it as the queue's element type, e.g.
+/// `MpmcQueue<Aligned<[u64; 4], A32>>`, to get 32-byte aligned payloads
+/// inside the slots.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(C)]
+pub struct Aligned<T, A: Alignment> {
+    _align: [A; 0],
+    value: T,
+}
+
+impl<T, A: Alignment> Aligned<T, A> {
+    /// Wraps a value.
+    pub const fn new(value: T) -> Self {
+        Self { _align: [], value }
+    }
+
+    /// Unwraps the value.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, A: Alignment> Deref for Aligned<T, A> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T, A: Alignment> DerefMut for Aligned<T, A> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+
+impl<T, A: Alignment> From<T> for Aligned<T, A> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: fmt::Debug, A: Alignment> fmt::Debug for Aligned<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
@@ -0,0 +1,174 @@
+//! Fixed-capacity MPMC queue with inline storage.
+
+use core::sync::atomic::Ordering;
+
+use crate::seq::{compare, depth, free_seq, next_seq, word};
+use crate::sync::AtomicUsize;
+use crate::{ConsumerPos, ProducerPos, Slot};
+
+/// A bounded MPMC queue whose `N` slots live inline instead of on the heap.
+///
+/// It runs the same sequence-number protocol as `MpmcQueue`, but the ring is
+/// an `[Slot<T>; N]` inside the queue itself, so creating one never
+/// allocates. That suits embedded and real-time code that cannot allocate:
+/// put it on the stack, in a struct, or share it across scoped threads.
+/// Each slot is cache-line aligned, so the queue takes about
+/// `N * CACHE_LINE` bytes.
+///
+/// `N` must be a power of two and at least 2, since a single slot cannot
+/// tell "written" apart from "free for the next lap". Anything else is
+/// rejected at compile time when `new` is instantiated:
+///
+/// ```compile_fail
+/// let queue = mpmc_std::ArrayMpmcQueue::<u32, 6>::new();
+/// ```
+///
+/// Only the non-blocking operations are provided.
+pub struct ArrayMpmcQueue<T, const N: usize> {
+    buffer: [Slot<T>; N],
+    producer_pos: ProducerPos,
+    consumer_pos: ConsumerPos,
+}
+
+impl<T, const N: usize> ArrayMpmcQueue<T, N> {
+    const MASK: usize = N - 1;
+
+    // Evaluated when `new` is instantiated, failing the build for a bad `N`
+    const VALID_CAPACITY: () = assert!(
+        N >= 2 && N.is_power_of_two() && N <= crate::seq::MAX_CAPACITY,
+        "ArrayMpmcQueue capacity must be a power of two of at least 2 that fits the sequence numbers"
+    );
+
+    /// Creates an empty queue.
+    pub fn new() -> Self {
+        let () = Self::VALID_CAPACITY;
+        Self {
+            buffer: core::array::from_fn(Slot::new),
+            producer_pos: ProducerPos {
+                head: AtomicUsize::new(0),
+            },
+            consumer_pos: ConsumerPos {
+                tail: AtomicUsize::new(0),
+            },
+        }
+    }
+
+    /// Attempts to send an item, handing it back if the queue is full.
+    pub fn send(&self, item: T) -> Result<(), T> {
+        loop {
+            let head = self.producer_pos.head.load(Ordering::Relaxed);
+            let slot = &self.buffer[head & Self::MASK];
+            let seq = slot.sequence.load(Ordering::Acquire);
+
+            match compare(seq, head) {
+                core::cmp::Ordering::Equal => {
+                    if self
+                        .producer_pos
+                        .head
+                        .compare_exchange_weak(head, next_seq(head), Ordering::Relaxed, Ordering::Relaxed)
+                        .is_ok()
+                    {
+                        unsafe {
+                            slot.data.with_mut(|data| (*data).write(item));
+                        }
+                        slot.sequence.store(word(next_seq(head)), Ordering::Release);
+                        return Ok(());
+                    }
+                }
+                core::cmp::Ordering::Less => {
+                    // The slot still holds last lap's item, check for full
+                    let tail = self.consumer_pos.tail.load(Ordering::Acquire);
+                    if depth(head, tail) >= N {
+                        return Err(item);
+                    }
+                }
+                core::cmp::Ordering::Greater => {
+                    // Another producer claimed `head`, wait until we see it move
+                    while self.producer_pos.head.load(Ordering::Acquire) == head {
+                        core::hint::spin_loop();
+                    }
+                    continue;
+                }
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Attempts to receive an item, returning None if the queue is empty.
+    pub fn recv(&self) -> Option<T> {
+        loop {
+            let tail = self.consumer_pos.tail.load(Ordering::Relaxed);
+            let slot = &self.buffer[tail & Self::MASK];
+            let seq = slot.sequence.load(Ordering::Acquire);
+
+            match compare(seq, next_seq(tail)) {
+                core::cmp::Ordering::Equal => {
+                    if self
+                        .consumer_pos
+                        .tail
+                        .compare_exchange_weak(tail, next_seq(tail), Ordering::Relaxed, Ordering::Relaxed)
+                        .is_ok()
+                    {
+                        let item = unsafe { slot.take() };
+                        slot.sequence.store(word(free_seq(tail, N)), Ordering::Release);
+                        return Some(item);
+                    }
+                }
+                core::cmp::Ordering::Less => return None,
+                core::cmp::Ordering::Greater => {
+                    // Another consumer claimed `tail`, wait until we see it move
+                    while self.consumer_pos.tail.load(Ordering::Acquire) == tail {
+                        core::hint::spin_loop();
+                    }
+                    continue;
+                }
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Returns the capacity, `N`.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the approximate number of items in the queue.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn len(&self) -> usize {
+        // Tail first, so a consumer racing ahead cannot make the depth
+        // wrap; clamped since producers may move on in between too
+        let tail = self.consumer_pos.tail.load(Ordering::Acquire);
+        let head = self.producer_pos.head.load(Ordering::Acquire);
+        depth(head, tail).min(N)
+    }
+
+    /// Returns true if the queue is empty.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true if the queue is full.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn is_full(&self) -> bool {
+        self.len() >= N
+    }
+}
+
+impl<T, const N: usize> Default for ArrayMpmcQueue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+unsafe impl<T: Send, const N: usize> Send for ArrayMpmcQueue<T, N> {}
+unsafe impl<T: Send, const N: usize> Sync for ArrayMpmcQueue<T, N> {}
+
+impl<T, const N: usize> Drop for ArrayMpmcQueue<T, N> {
+    fn drop(&mut self) {
+        while self.recv().is_some() {}
+    }
+}
@@ -0,0 +1,209 @@
+use std::cell::UnsafeCell;
+use std::mem::MaybeUninit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::seq::{depth, next_seq};
+use crate::wait_strategy::{Backoff, WaitStrategy};
+
+/// Fan-out queue: every subscribed consumer receives a clone of every item.
+///
+/// Producers share one ring of sequence-numbered slots like `MpmcQueue`,
+/// but consumers do not compete for items. Each `BroadcastConsumer` keeps
+/// its own read cursor, and a slot is only reused once every consumer has
+/// read past it. `send` therefore fails when the slowest consumer is
+/// `capacity` items behind.
+///
+/// A consumer starts at the items sent after it subscribed. Producers find
+/// the slowest consumer by scanning the cursors under a lock, but only when
+/// the cached minimum no longer leaves room, so that cost is paid about once
+/// per lap rather than per send. Items sent with no subscriber are kept
+/// until the ring is full, then overwritten.
+pub struct BroadcastQueue<T> {
+    buffer: Box<[BroadcastSlot<T>]>,
+    capacity: usize,
+    mask: usize,
+    head: AtomicUsize,
+    // Lower bound on every cursor; slots before it may be reused
+    min_cursor: AtomicUsize,
+    cursors: Mutex<Vec<Arc<AtomicUsize>>>,
+}
+
+#[repr(align(64))]
+#[cfg_attr(all(target_arch = "aarch64", target_vendor = "apple"), repr(align(128)))]
+#[cfg_attr(target_arch = "s390x", repr(align(256)))]
+struct BroadcastSlot<T> {
+    sequence: AtomicUsize,
+    data: UnsafeCell<MaybeUninit<T>>,
+}
+
+impl<T: Clone + Send + Sync> BroadcastQueue<T> {
+    /// Creates a queue with the given capacity, rounded up to a power of 2
+    /// of at least 2, and no subscribers.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "Capacity must be greater than 0");
+
+        let capacity = capacity.next_power_of_two().max(2);
+        let buffer: Vec<_> = (0..capacity)
+            .map(|i| BroadcastSlot {
+                sequence: AtomicUsize::new(i),
+                data: UnsafeCell::new(MaybeUninit::uninit()),
+            })
+            .collect();
+
+        Self {
+            buffer: buffer.into_boxed_slice(),
+            capacity,
+            mask: capacity - 1,
+            head: AtomicUsize::new(0),
+            min_cursor: AtomicUsize::new(0),
+            cursors: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Registers a new consumer that receives every item sent from now on.
+    pub fn subscribe(self: &Arc<Self>) -> BroadcastConsumer<T> {
+        let mut cursors = self.cursors.lock().unwrap();
+        // Under the lock, so no producer computes a minimum without it
+        let cursor = Arc::new(AtomicUsize::new(self.head.load(Ordering::Acquire)));
+        cursors.push(Arc::clone(&cursor));
+        BroadcastConsumer {
+            queue: Arc::clone(self),
+            cursor,
+        }
+    }
+
+    /// Attempts to send an item to every subscriber.
+    ///
+    /// Returns the item back if the slowest consumer has not yet read the
+    /// item that would be overwritten.
+    pub fn send(&self, item: T) -> Result<(), T> {
+        let backoff = Backoff::default();
+        let mut attempt = 0u32;
+        loop {
+            let head = self.head.load(Ordering::Relaxed);
+            let slot = &self.buffer[head & self.mask];
+            let seq = slot.sequence.load(Ordering::Acquire);
+
+            // Never written, or still holding the item from one lap ago
+            let reused = seq == next_seq(head.wrapping_sub(self.capacity));
+            if seq == head || reused {
+                if reused && !self.reclaimable(head) {
+                    return Err(item);
+                }
+                if self
+                    .head
+                    .compare_exchange_weak(head, next_seq(head), Ordering::Relaxed, Ordering::Relaxed)
+                    .is_ok()
+                {
+                    unsafe {
+                        if reused {
+                            (*slot.data.get()).assume_init_drop();
+                        }
+                        (*slot.data.get()).write(item);
+                    }
+                    slot.sequence.store(next_seq(head), Ordering::Release);
+                    return Ok(());
+                }
+            }
+            // Lost the race for the slot, or another producer is mid-write
+            backoff.wait(attempt);
+            attempt = attempt.saturating_add(1);
+        }
+    }
+
+    /// Returns the capacity of the queue.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of subscribed consumers.
+    pub fn subscriber_count(&self) -> usize {
+        self.cursors.lock().unwrap().len()
+    }
+
+    /// True if every consumer has read the item last sent at `head`'s slot.
+    fn reclaimable(&self, head: usize) -> bool {
+        if self.lag(head, self.min_cursor.load(Ordering::Acquire)) < self.capacity as isize {
+            return true;
+        }
+
+        // Recompute the slowest cursor; without subscribers, only the
+        // producers' own position bounds reuse
+        let cursors = self.cursors.lock().unwrap();
+        let min = cursors
+            .iter()
+            .map(|cursor| cursor.load(Ordering::Acquire))
+            .max_by_key(|&cursor| self.lag(head, cursor))
+            .unwrap_or(head);
+        self.min_cursor.store(min, Ordering::Release);
+        self.lag(head, min) < self.capacity as isize
+    }
+
+    // How far `cursor` trails `head`; negative if `head` is a stale load
+    // that a consumer has already read past
+    fn lag(&self, head: usize, cursor: usize) -> isize {
+        head.wrapping_sub(cursor) as isize
+    }
+}
+
+unsafe impl<T: Send + Sync> Send for BroadcastQueue<T> {}
+unsafe impl<T: Send + Sync> Sync for BroadcastQueue<T> {}
+
+impl<T> Drop for BroadcastQueue<T> {
+    fn drop(&mut self) {
+        // A slot holds an item iff its sequence marks a write at its index
+        for (i, slot) in self.buffer.iter_mut().enumerate() {
+            if slot.sequence.get_mut().wrapping_sub(1) & self.mask == i {
+                unsafe {
+                    slot.data.get_mut().assume_init_drop();
+                }
+            }
+        }
+    }
+}
+
+/// A subscriber to a `BroadcastQueue`, created by `BroadcastQueue::subscribe`.
+///
+/// Dropping it unsubscribes, so it no longer holds producers back.
+pub struct BroadcastConsumer<T> {
+    queue: Arc<BroadcastQueue<T>>,
+    cursor: Arc<AtomicUsize>,
+}
+
+impl<T: Clone + Send + Sync> BroadcastConsumer<T> {
+    /// Receives a clone of the next item, or None if this consumer has
+    /// already seen every item sent so far.
+    pub fn recv(&mut self) -> Option<T> {
+        let pos = self.cursor.load(Ordering::Relaxed);
+        let slot = &self.queue.buffer[pos & self.queue.mask];
+        if slot.sequence.load(Ordering::Acquire) != next_seq(pos) {
+            return None;
+        }
+
+        // Producers cannot reuse the slot until the cursor moves past it
+        let item = unsafe { (*slot.data.get()).assume_init_ref().clone() };
+        self.cursor.store(next_seq(pos), Ordering::Release);
+        Some(item)
+    }
+
+    /// Returns the number of items this consumer has yet to receive.
+    pub fn len(&self) -> usize {
+        depth(
+            self.queue.head.load(Ordering::Acquire),
+            self.cursor.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Returns true if this consumer has received every item sent so far.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Drop for BroadcastConsumer<T> {
+    fn drop(&mut self) {
+        let mut cursors = self.queue.cursors.lock().unwrap();
+        cursors.retain(|cursor| !Arc::ptr_eq(cursor, &self.cursor));
+    }
+}
@@ -0,0 +1,101 @@
+//! Producer wrapper that coalesces single sends into batches.
+
+use std::collections::VecDeque;
+
+use crate::Producer;
+
+/// A producer handle that buffers sends and pushes them into the queue in
+/// batches.
+///
+/// Each `send` on a plain `Producer` claims its slot with its own
+/// `compare_exchange` on the head. This wrapper instead collects items in a
+/// local buffer and, once `threshold` of them have built up, claims a run
+/// of contiguous slots for all of them at once, as `MpmcQueue::send_iter`
+/// does. Call sites that produce one item at a time get batch throughput
+/// without being restructured.
+///
+/// Buffered items are invisible to consumers until flushed, so call `flush`
+/// at natural pauses (the end of a burst, before waiting on a reply) to
+/// bound their latency. The buffer is flushed when the wrapper is dropped,
+/// which blocks until the queue has room for everything still buffered.
+///
+/// Items keep their order relative to each other, but items sent through
+/// other handles may land between two batches. Like other batched sends,
+/// they carry no producer id: `recv_tagged` reports `None` for them.
+pub struct BufferedProducer<T: Send> {
+    producer: Producer<T>,
+    buffer: VecDeque<T>,
+    threshold: usize,
+}
+
+impl<T: Send> BufferedProducer<T> {
+    /// Wraps a producer handle, flushing whenever `threshold` items are
+    /// buffered.
+    pub fn new(producer: Producer<T>, threshold: usize) -> Self {
+        assert!(threshold > 0, "Threshold must be greater than 0");
+        Self {
+            producer,
+            buffer: VecDeque::with_capacity(threshold),
+            threshold,
+        }
+    }
+
+    /// Buffers an item, flushing the buffer once it reaches the threshold.
+    ///
+    /// The flush blocks while the queue is full, like `flush`.
+    pub fn send(&mut self, item: T) {
+        self.buffer.push_back(item);
+        if self.buffer.len() >= self.threshold {
+            self.flush();
+        }
+    }
+
+    /// Sends every buffered item, parking while the queue is full until
+    /// consumers make room.
+    pub fn flush(&mut self) {
+        while self.try_flush().is_err() {
+            // Wait for room with the oldest item, then batch the rest again
+            let item = self.buffer.pop_front().expect("a failed flush leaves items buffered");
+            self.producer.send_blocking(item);
+        }
+    }
+
+    /// Sends as many buffered items as fit without waiting.
+    ///
+    /// Returns `Err(n)` with the number of items still buffered if the
+    /// queue filled up first.
+    pub fn try_flush(&mut self) -> Result<(), usize> {
+        let queue = &self.producer.queue;
+        while !self.buffer.is_empty() {
+            let buffer = &mut self.buffer;
+            let claimed = queue.send_run(buffer.len(), |_, _| {
+                buffer.pop_front().expect("never claims more slots than items buffered")
+            });
+            if claimed == 0 {
+                return Err(self.buffer.len());
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the number of items buffered but not yet sent.
+    pub fn buffered(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Returns the number of buffered items that triggers a flush.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Returns the wrapped producer handle.
+    pub fn producer(&self) -> &Producer<T> {
+        &self.producer
+    }
+}
+
+impl<T: Send> Drop for BufferedProducer<T> {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
@@ -0,0 +1,187 @@
+//! MPMC byte ring for streaming raw bytes.
+
+use alloc::boxed::Box;
+use core::cell::UnsafeCell;
+use core::ptr;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::seq::depth;
+use crate::wait_strategy::{Backoff, WaitStrategy};
+
+/// A bounded ring of bytes shared by any number of writers and readers.
+///
+/// Where `MpmcQueue<u8>` would spend a 64-byte slot and a sequence number
+/// per byte, this stores bytes back to back in one `Box<[u8]>`, and a
+/// `write` or `read` moves a whole run with at most two `memcpy`s (one when
+/// the run wraps past the end of the buffer).
+///
+/// Each side has two cursors. A writer claims a run of free bytes by
+/// advancing the `reserved` cursor with a compare-exchange, copies into it,
+/// then advances `committed` past it; readers only read up to `committed`.
+/// Readers mirror this, and writers only reuse bytes behind the readers'
+/// `committed` cursor. Runs commit in the order they were claimed, so a
+/// writer that finishes copying before an earlier one spins until the
+/// earlier one commits.
+///
+/// The ring carries a byte stream, not messages: the bytes of one `write`
+/// stay contiguous and in order, but a `read` may return part of a write,
+/// or the end of one and the start of the next.
+pub struct ByteRing {
+    buffer: Box<[UnsafeCell<u8>]>,
+    mask: usize,
+    write: Cursor,
+    read: Cursor,
+}
+
+#[repr(align(64))] // Align to cache line to avoid false sharing
+#[cfg_attr(all(target_arch = "aarch64", target_vendor = "apple"), repr(align(128)))]
+#[cfg_attr(target_arch = "s390x", repr(align(256)))]
+struct Cursor {
+    // End of the runs claimed so far
+    reserved: AtomicUsize,
+    // End of the runs fully copied
+    committed: AtomicUsize,
+}
+
+impl Cursor {
+    fn new() -> Self {
+        Self {
+            reserved: AtomicUsize::new(0),
+            committed: AtomicUsize::new(0),
+        }
+    }
+
+    // Claims up to `wanted` bytes of the `available(reserved)` on offer,
+    // returning the start and length of the run
+    fn reserve(&self, wanted: usize, available: impl Fn(usize) -> usize) -> Option<(usize, usize)> {
+        let backoff = Backoff::default();
+        let mut attempt = 0u32;
+        loop {
+            // Acquire/Release chain the cursors: whoever sees this run
+            // claimed also sees the other side's cursor as it was checked,
+            // so `available` never runs on a position it has not caught up to
+            let start = self.reserved.load(Ordering::Acquire);
+            let len = wanted.min(available(start));
+            if len == 0 {
+                return None;
+            }
+            if self
+                .reserved
+                .compare_exchange_weak(start, start.wrapping_add(len), Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+            {
+                return Some((start, len));
+            }
+            backoff.wait(attempt);
+            attempt = attempt.saturating_add(1);
+        }
+    }
+
+    // Publishes a copied run once every run claimed before it is published
+    fn commit(&self, start: usize, len: usize) {
+        let backoff = Backoff::default();
+        let mut attempt = 0u32;
+        while self.committed.load(Ordering::Acquire) != start {
+            backoff.wait(attempt);
+            attempt = attempt.saturating_add(1);
+        }
+        // Release: the other side's Acquire load of `committed` sees the copy
+        self.committed.store(start.wrapping_add(len), Ordering::Release);
+    }
+}
+
+impl ByteRing {
+    /// Creates a ring holding up to `capacity` bytes, rounded up to a power
+    /// of 2.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "Capacity must be greater than 0");
+
+        let capacity = capacity.next_power_of_two();
+        Self {
+            buffer: (0..capacity).map(|_| UnsafeCell::new(0)).collect(),
+            mask: capacity - 1,
+            write: Cursor::new(),
+            read: Cursor::new(),
+        }
+    }
+
+    /// Writes as much of `data` as fits, returning the number of bytes
+    /// written (0 if the ring is full).
+    pub fn write(&self, data: &[u8]) -> usize {
+        let capacity = self.capacity();
+        let Some((start, len)) = self.write.reserve(data.len(), |reserved| {
+            // Acquire: pairs with the readers' commit, so their copies out
+            // are done before these bytes are overwritten. A stale
+            // `reserved` can trail the readers; the exchange then fails
+            let used = depth(reserved, self.read.committed.load(Ordering::Acquire));
+            capacity.checked_sub(used).unwrap_or(capacity)
+        }) else {
+            return 0;
+        };
+
+        let (first, second) = self.split(start, len);
+        unsafe {
+            ptr::copy_nonoverlapping(data.as_ptr(), self.base().add(start & self.mask), first);
+            ptr::copy_nonoverlapping(data.as_ptr().add(first), self.base(), second);
+        }
+        self.write.commit(start, len);
+        len
+    }
+
+    /// Reads up to `out.len()` bytes into `out`, returning the number of
+    /// bytes read (0 if the ring is empty).
+    pub fn read(&self, out: &mut [u8]) -> usize {
+        let Some((start, len)) = self.read.reserve(out.len(), |reserved| {
+            depth(self.write.committed.load(Ordering::Acquire), reserved)
+        }) else {
+            return 0;
+        };
+
+        let (first, second) = self.split(start, len);
+        unsafe {
+            ptr::copy_nonoverlapping(self.base().add(start & self.mask), out.as_mut_ptr(), first);
+            ptr::copy_nonoverlapping(self.base(), out.as_mut_ptr().add(first), second);
+        }
+        self.read.commit(start, len);
+        len
+    }
+
+    /// Returns the number of bytes ready to be read.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn available_read(&self) -> usize {
+        // Readers never pass the writers' commit, so loading theirs first
+        // keeps the difference from going negative
+        let reserved = self.read.reserved.load(Ordering::Acquire);
+        depth(self.write.committed.load(Ordering::Acquire), reserved)
+    }
+
+    /// Returns the number of bytes that can be written without overwriting
+    /// unread data.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn available_write(&self) -> usize {
+        let committed = self.read.committed.load(Ordering::Acquire);
+        let used = depth(self.write.reserved.load(Ordering::Relaxed), committed);
+        self.capacity() - used.min(self.capacity())
+    }
+
+    /// Returns the capacity of the ring in bytes.
+    pub fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+
+    // Lengths of a run before and after it wraps past the end of the buffer
+    fn split(&self, start: usize, len: usize) -> (usize, usize) {
+        let first = len.min(self.capacity() - (start & self.mask));
+        (first, len - first)
+    }
+
+    // Derived from the whole buffer so copies may span several bytes
+    fn base(&self) -> *mut u8 {
+        UnsafeCell::raw_get(self.buffer.as_ptr())
+    }
+}
+
+unsafe impl Send for ByteRing {}
+unsafe impl Sync for ByteRing {}
@@ -0,0 +1,130 @@
+use std::collections::HashSet;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hash};
+use std::sync::Mutex;
+
+use crate::MpmcQueue;
+use crate::error::DedupSendError;
+
+// Number of independently locked parts of the key set
+const STRIPES: usize = 16;
+
+/// MPMC queue that rejects an item while an equal one is still queued.
+///
+/// Built for idempotent event processing: sending the same event twice
+/// before it is handled enqueues it once. Items are compared by a key,
+/// the item itself for `new` or any `Hash + Eq` value derived from it for
+/// `with_key`, e.g. an event id.
+///
+/// The keys of the queued items are kept in a set next to the ring. The set
+/// is split into stripes, each behind its own lock and picked by the key's
+/// hash, so senders and receivers only contend when their keys land in the
+/// same stripe, and a lock is only held for one set insert or removal,
+/// never across a ring operation. A send records the key before the item
+/// enters the ring, and a receive forgets it only after taking the item
+/// out, so every queued item's key is in the set. The reverse does not
+/// quite hold: around a send that fails on a full queue, and between a
+/// receive and its key removal, a key can be in the set without its item
+/// being queued, and a concurrent send of an equal item is then rejected as
+/// a duplicate. Rejections are never wrong in the other direction: two
+/// equal items are never queued at once.
+pub struct DedupMpmcQueue<T, K = T> {
+    queue: MpmcQueue<T>,
+    key: Box<dyn Fn(&T) -> K + Send + Sync>,
+    keys: Box<[Stripe<K>]>,
+    hasher: RandomState,
+}
+
+#[repr(align(64))] // Align to cache line to avoid false sharing
+#[cfg_attr(all(target_arch = "aarch64", target_vendor = "apple"), repr(align(128)))]
+#[cfg_attr(target_arch = "s390x", repr(align(256)))]
+struct Stripe<K> {
+    keys: Mutex<HashSet<K>>,
+}
+
+impl<T: Send + Hash + Eq + Clone + 'static> DedupMpmcQueue<T> {
+    /// Creates a queue that compares items themselves, keeping a clone of
+    /// each queued item as its key.
+    ///
+    /// Capacity is rounded up to a power of 2 as in `MpmcQueue::new`.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_key(capacity, T::clone)
+    }
+}
+
+impl<T: Send, K: Hash + Eq> DedupMpmcQueue<T, K> {
+    /// Creates a queue that compares items by `key(&item)`.
+    ///
+    /// Capacity is rounded up to a power of 2 as in `MpmcQueue::new`.
+    pub fn with_key(capacity: usize, key: impl Fn(&T) -> K + Send + Sync + 'static) -> Self {
+        Self {
+            queue: MpmcQueue::new(capacity),
+            key: Box::new(key),
+            keys: (0..STRIPES)
+                .map(|_| Stripe {
+                    keys: Mutex::new(HashSet::new()),
+                })
+                .collect(),
+            hasher: RandomState::new(),
+        }
+    }
+
+    /// Sends an item unless an item with the same key is already queued.
+    ///
+    /// Returns `Err(DedupSendError::Duplicate(item))` if one is, and
+    /// `Err(DedupSendError::Full(item))` if the queue is full.
+    pub fn send(&self, item: T) -> Result<(), DedupSendError<T>> {
+        let key = (self.key)(&item);
+        let stripe = self.stripe(&key);
+        // Claim the key before the item becomes visible to receivers
+        if !stripe.keys.lock().unwrap().insert(key) {
+            return Err(DedupSendError::Duplicate(item));
+        }
+
+        self.queue.send(item).map_err(|item| {
+            // Keys need not be `Clone`, so derive it again
+            stripe.keys.lock().unwrap().remove(&(self.key)(&item));
+            DedupSendError::Full(item)
+        })
+    }
+
+    /// Receives an item, or returns None if the queue is empty.
+    ///
+    /// Once this returns, an equal item can be sent again.
+    pub fn recv(&self) -> Option<T> {
+        let item = self.queue.recv()?;
+        let key = (self.key)(&item);
+        self.stripe(&key).keys.lock().unwrap().remove(&key);
+        Some(item)
+    }
+
+    /// Returns true if an item with `key` is queued or being sent.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.stripe(key).keys.lock().unwrap().contains(key)
+    }
+
+    /// Returns the approximate number of items in the queue.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns true if the queue is empty.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Returns the capacity of the queue.
+    pub fn capacity(&self) -> usize {
+        self.queue.capacity()
+    }
+
+    fn stripe(&self, key: &K) -> &Stripe<K> {
+        &self.keys[self.hasher.hash_one(key) as usize % STRIPES]
+    }
+}
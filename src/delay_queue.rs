@@ -0,0 +1,181 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error::RecvTimeoutError;
+
+/// Bounded MPMC queue whose items become receivable at a given time.
+///
+/// A delay queue for retries with backoff and other scheduled work: `send`
+/// takes the instant an item is due, and `recv` only hands out items whose
+/// time has come, earliest due first. Items due at the same instant come
+/// out in the order they were sent.
+///
+/// Items are ordered by due time rather than arrival, so this is a binary
+/// heap behind a mutex instead of the lock-free ring: every operation takes
+/// the lock for an O(log n) heap update. `recv_timeout` parks on a condvar
+/// until the earliest item is due or a send brings an earlier one.
+pub struct DelayQueue<T> {
+    heap: Mutex<Heap<T>>,
+    ready: Condvar,
+    capacity: usize,
+}
+
+struct Heap<T> {
+    entries: BinaryHeap<Entry<T>>,
+    // Send order, breaking ties between items due at the same instant
+    next_seq: u64,
+}
+
+struct Entry<T> {
+    // None for a delay too long to represent: never due
+    deliver_at: Option<Instant>,
+    seq: u64,
+    item: T,
+}
+
+impl<T> Entry<T> {
+    // Items that are never due sort after every other item
+    fn key(&self) -> (bool, Option<Instant>, u64) {
+        (self.deliver_at.is_none(), self.deliver_at, self.seq)
+    }
+}
+
+// Reversed so the max-heap yields the earliest due item, then the first sent
+impl<T> Ord for Entry<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.key().cmp(&self.key())
+    }
+}
+
+impl<T> PartialOrd for Entry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> PartialEq for Entry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Entry<T> {}
+
+impl<T> Heap<T> {
+    // Pops the earliest item if it is due, otherwise returns when it will be
+    fn pop_due(&mut self, now: Instant) -> Result<T, Option<Instant>> {
+        match self.entries.peek() {
+            Some(entry) if entry.deliver_at.is_some_and(|at| at <= now) => Ok(self.entries.pop().unwrap().item),
+            Some(entry) => Err(entry.deliver_at),
+            None => Err(None),
+        }
+    }
+}
+
+impl<T: Send> DelayQueue<T> {
+    /// Creates a queue holding up to `capacity` items, waiting or due.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "Capacity must be greater than 0");
+
+        Self {
+            heap: Mutex::new(Heap {
+                entries: BinaryHeap::with_capacity(capacity),
+                next_seq: 0,
+            }),
+            ready: Condvar::new(),
+            capacity,
+        }
+    }
+
+    /// Sends an item that becomes receivable at `deliver_at`.
+    ///
+    /// An instant in the past makes it receivable right away. Returns the
+    /// item back if the queue is full.
+    pub fn send(&self, item: T, deliver_at: Instant) -> Result<(), T> {
+        self.send_due(item, Some(deliver_at))
+    }
+
+    /// Sends an item that becomes receivable after `delay`.
+    ///
+    /// A delay too long to represent as an `Instant` makes the item never
+    /// due: it stays queued, taking up capacity, until the queue is dropped.
+    pub fn send_after(&self, item: T, delay: Duration) -> Result<(), T> {
+        self.send_due(item, Instant::now().checked_add(delay))
+    }
+
+    fn send_due(&self, item: T, deliver_at: Option<Instant>) -> Result<(), T> {
+        let mut heap = self.heap.lock().unwrap();
+        if heap.entries.len() >= self.capacity {
+            return Err(item);
+        }
+        let seq = heap.next_seq;
+        heap.next_seq += 1;
+        heap.entries.push(Entry { deliver_at, seq, item });
+        drop(heap);
+        // A parked receiver may be waiting for a later item than this one
+        self.ready.notify_one();
+        Ok(())
+    }
+
+    /// Receives the earliest due item, or returns None if no item is due
+    /// yet.
+    pub fn recv(&self) -> Option<T> {
+        self.heap.lock().unwrap().pop_due(Instant::now()).ok()
+    }
+
+    /// Receives the earliest due item, parking for at most `timeout` until
+    /// one is due.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        self.recv_deadline_opt(Instant::now().checked_add(timeout))
+    }
+
+    /// Receives the earliest due item, parking until one is due or
+    /// `deadline` passes.
+    pub fn recv_deadline(&self, deadline: Instant) -> Result<T, RecvTimeoutError> {
+        self.recv_deadline_opt(Some(deadline))
+    }
+
+    fn recv_deadline_opt(&self, deadline: Option<Instant>) -> Result<T, RecvTimeoutError> {
+        let mut heap = self.heap.lock().unwrap();
+        loop {
+            let now = Instant::now();
+            let next_due = match heap.pop_due(now) {
+                Ok(item) => return Ok(item),
+                Err(next_due) => next_due,
+            };
+            // Wake when the earliest item is due, or give up at the deadline
+            let wake_at = match (next_due, deadline) {
+                (Some(due), Some(deadline)) => Some(due.min(deadline)),
+                (due, deadline) => due.or(deadline),
+            };
+            heap = match wake_at {
+                Some(wake_at) if wake_at <= now => return Err(RecvTimeoutError::Timeout),
+                Some(wake_at) => self.ready.wait_timeout(heap, wake_at - now).unwrap().0,
+                None => self.ready.wait(heap).unwrap(),
+            };
+        }
+    }
+
+    /// Returns when the earliest item is due, or None if the queue is empty
+    /// or holds only items that are never due.
+    pub fn next_deliver_at(&self) -> Option<Instant> {
+        self.heap.lock().unwrap().entries.peek().and_then(|entry| entry.deliver_at)
+    }
+
+    /// Returns the number of items in the queue, due or not.
+    pub fn len(&self) -> usize {
+        self.heap.lock().unwrap().entries.len()
+    }
+
+    /// Returns true if the queue holds no items, due or not.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the capacity of the queue.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
@@ -0,0 +1,82 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::MpmcQueue;
+
+/// MPMC queue for real-time producers: `send` never blocks or fails, `recv` blocks.
+///
+/// This matches the audio/DSP pattern where the producer (e.g. an audio
+/// callback) must never wait and stale data can be discarded, while the
+/// consumer (a processing thread) should sleep when there is nothing to do.
+///
+/// Producer side:
+/// - `send` always enqueues the new item
+/// - When the ring is full, the oldest item is evicted to make room and handed
+///   back to the caller, so dropping it happens wherever the caller chooses
+/// - Every eviction is counted in `dropped_count`, letting consumers detect
+///   that samples were lost
+///
+/// Consumer side:
+/// - `recv` parks the calling thread until an item arrives
+/// - `try_recv` is the non-blocking variant
+pub struct ElasticMpmcQueue<T> {
+    queue: MpmcQueue<T>,
+    dropped: AtomicU64,
+}
+
+impl<T: Send> ElasticMpmcQueue<T> {
+    /// Creates a new elastic queue with the specified capacity.
+    ///
+    /// The capacity is rounded up to the next power of 2.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            queue: MpmcQueue::new(capacity),
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Sends an item, evicting the oldest item if the queue is full.
+    ///
+    /// Never blocks and never fails. Returns the evicted item, if any.
+    pub fn send(&self, item: T) -> Option<T> {
+        let mut evicted = None;
+        self.queue.send_evicting(item, |oldest| {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            evicted = Some(oldest);
+        });
+        evicted
+    }
+
+    /// Receives an item, parking the calling thread until one is available.
+    pub fn recv(&self) -> T {
+        self.queue.recv_blocking()
+    }
+
+    /// Attempts to receive an item without blocking.
+    pub fn try_recv(&self) -> Option<T> {
+        self.queue.recv()
+    }
+
+    /// Returns the number of items evicted because the queue was full.
+    pub fn dropped_count(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Returns the capacity of the queue.
+    pub fn capacity(&self) -> usize {
+        self.queue.capacity()
+    }
+
+    /// Returns true if the queue is empty.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Returns the approximate number of items in the queue.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+}
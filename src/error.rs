@@ -0,0 +1,197 @@
+//! Error types for queue construction and the blocking and checked
+//! queue operations.
+
+use core::error::Error;
+use core::fmt;
+
+/// Error returned by `recv_disconnected` once no item can arrive anymore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvError {
+    /// The queue is empty and every `Producer` handle has been dropped.
+    Disconnected,
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecvError::Disconnected => f.write_str("receiving on an empty and disconnected queue"),
+        }
+    }
+}
+
+impl Error for RecvError {}
+
+/// Error returned by `try_recv`, named after `std::sync::mpsc::TryRecvError`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// The queue is empty right now; a later receive may succeed.
+    Empty,
+    /// The queue is empty and every `Producer` handle has been dropped.
+    Disconnected,
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryRecvError::Empty => f.write_str("receiving on an empty queue"),
+            TryRecvError::Disconnected => f.write_str("receiving on an empty and disconnected queue"),
+        }
+    }
+}
+
+impl Error for TryRecvError {}
+
+/// Error returned by `recv_timeout` and `recv_deadline`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecvTimeoutError {
+    /// No item arrived before the timeout elapsed.
+    Timeout,
+}
+
+impl fmt::Display for RecvTimeoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RecvTimeoutError::Timeout => f.write_str("timed out waiting on receive operation"),
+        }
+    }
+}
+
+impl Error for RecvTimeoutError {}
+
+/// Error returned by `send_timeout` and `send_deadline`, handing the
+/// unsent item back.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SendTimeoutError<T> {
+    /// The queue stayed full until the timeout elapsed.
+    Timeout(T),
+}
+
+impl<T> SendTimeoutError<T> {
+    /// Returns the item that could not be sent.
+    pub fn into_inner(self) -> T {
+        match self {
+            SendTimeoutError::Timeout(item) => item,
+        }
+    }
+}
+
+// Like std's channel errors, don't require T: Debug
+impl<T> fmt::Debug for SendTimeoutError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendTimeoutError::Timeout(..) => f.write_str("Timeout(..)"),
+        }
+    }
+}
+
+impl<T> fmt::Display for SendTimeoutError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendTimeoutError::Timeout(..) => f.write_str("timed out waiting on send operation"),
+        }
+    }
+}
+
+impl<T> Error for SendTimeoutError<T> {}
+
+/// Error returned by `Producer::send_checked`, handing the unsent item back.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SendError<T> {
+    /// The queue is full right now; a later send may succeed.
+    Full(T),
+    /// Every consumer handle has been dropped, so nothing would receive it.
+    Disconnected(T),
+}
+
+impl<T> SendError<T> {
+    /// Returns the item that could not be sent.
+    pub fn into_inner(self) -> T {
+        match self {
+            SendError::Full(item) | SendError::Disconnected(item) => item,
+        }
+    }
+}
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendError::Full(..) => f.write_str("Full(..)"),
+            SendError::Disconnected(..) => f.write_str("Disconnected(..)"),
+        }
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SendError::Full(..) => f.write_str("sending on a full queue"),
+            SendError::Disconnected(..) => f.write_str("sending on a queue with no consumers"),
+        }
+    }
+}
+
+impl<T> Error for SendError<T> {}
+
+/// Error returned by `Producer::try_send`, under the name std's and
+/// crossbeam's channels use; its variants are `SendError`'s.
+pub type TrySendError<T> = SendError<T>;
+
+/// Error returned by `DedupMpmcQueue::send`, handing the unsent item back.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum DedupSendError<T> {
+    /// The queue is full right now; a later send may succeed.
+    Full(T),
+    /// An item with the same key is already queued.
+    Duplicate(T),
+}
+
+impl<T> DedupSendError<T> {
+    /// Returns the item that could not be sent.
+    pub fn into_inner(self) -> T {
+        match self {
+            DedupSendError::Full(item) | DedupSendError::Duplicate(item) => item,
+        }
+    }
+}
+
+impl<T> fmt::Debug for DedupSendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DedupSendError::Full(..) => f.write_str("Full(..)"),
+            DedupSendError::Duplicate(..) => f.write_str("Duplicate(..)"),
+        }
+    }
+}
+
+impl<T> fmt::Display for DedupSendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DedupSendError::Full(..) => f.write_str("sending on a full queue"),
+            DedupSendError::Duplicate(..) => f.write_str("an equal item is already queued"),
+        }
+    }
+}
+
+impl<T> Error for DedupSendError<T> {}
+
+/// Error returned by `MpmcQueue::try_new` for a capacity it cannot allocate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CapacityError {
+    /// The requested capacity was 0.
+    Zero,
+    /// Rounded up to a power of 2, the capacity overflows `usize` or exceeds
+    /// `seq::MAX_CAPACITY`, or the ring would be larger than an allocation
+    /// may be (`isize::MAX` bytes).
+    TooLarge,
+}
+
+impl fmt::Display for CapacityError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CapacityError::Zero => f.write_str("capacity must be greater than 0"),
+            CapacityError::TooLarge => f.write_str("capacity too large to allocate"),
+        }
+    }
+}
+
+impl Error for CapacityError {}
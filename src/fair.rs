@@ -0,0 +1,123 @@
+//! Ticket-ordered consumers that are served in arrival order.
+
+use alloc::sync::Arc;
+use core::fmt;
+use core::sync::atomic::Ordering;
+
+use crate::sync::AtomicUsize;
+use crate::MpmcQueue;
+
+/// A consumer of a queue created with `MpmcQueue::with_fair_consumers`.
+///
+/// Plain consumers race for the tail with `compare_exchange_weak`, and
+/// nothing stops an unlucky one from losing that race over and over while
+/// the queue is busy. Fair consumers instead take a ticket from a shared
+/// counter and receive in ticket order: a `recv` waits for at most one
+/// receive by each of the other fair consumers before it gets its turn,
+/// which bounds its latency by the number of consumers rather than by luck.
+///
+/// The turn makes receiving a critical section. Consumers no longer
+/// overlap their receives, and a consumer descheduled while holding its
+/// turn stalls the others until it runs again, so peak throughput with
+/// many consumers is lower than with `Consumer`. Waiting for a turn uses
+/// the queue's wait strategy. Within its turn a consumer is the only one
+/// touching the tail, so the receive itself skips the tail CAS, as
+/// `ExclusiveConsumer::recv` does.
+///
+/// Clone the handle for more consumers; the queue is never handed out, so
+/// every consumer of it is a fair one.
+pub struct FairConsumer<T> {
+    queue: Arc<MpmcQueue<T>>,
+    tickets: Arc<Tickets>,
+}
+
+#[repr(align(64))] // Align to cache line to avoid false sharing
+#[cfg_attr(all(target_arch = "aarch64", target_vendor = "apple"), repr(align(128)))]
+#[cfg_attr(target_arch = "s390x", repr(align(256)))]
+struct Tickets {
+    // Next ticket to hand out
+    next: AtomicUsize,
+    // Ticket whose holder may receive now
+    serving: AtomicUsize,
+}
+
+impl Tickets {
+    fn new() -> Self {
+        Self {
+            next: AtomicUsize::new(0),
+            serving: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<T: Send> FairConsumer<T> {
+    pub(crate) fn new(queue: Arc<MpmcQueue<T>>) -> Self {
+        queue.consumers.fetch_add(1, Ordering::Relaxed);
+        Self {
+            queue,
+            tickets: Arc::new(Tickets::new()),
+        }
+    }
+
+    /// Receives an item once every consumer that asked earlier has had its
+    /// turn.
+    ///
+    /// Returns None if the queue is empty, without taking a turn if it was
+    /// already empty on entry.
+    pub fn recv(&self) -> Option<T> {
+        if self.queue.is_empty() {
+            return None;
+        }
+
+        let ticket = self.tickets.next.fetch_add(1, Ordering::Relaxed);
+        let mut attempt = 0u32;
+        // Acquire: pairs with the previous holder's Release below, so its
+        // tail update is visible before this turn reads the tail
+        while self.tickets.serving.load(Ordering::Acquire) != ticket {
+            self.queue.retry_wait(&mut attempt);
+        }
+        let item = self.queue.recv_exclusive();
+        self.tickets.serving.store(ticket.wrapping_add(1), Ordering::Release);
+        item
+    }
+
+    /// Returns the approximate number of items in the queue.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns true if the queue is empty.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+impl<T: Send> Clone for FairConsumer<T> {
+    fn clone(&self) -> Self {
+        self.queue.consumers.fetch_add(1, Ordering::Relaxed);
+        Self {
+            queue: Arc::clone(&self.queue),
+            tickets: Arc::clone(&self.tickets),
+        }
+    }
+}
+
+impl<T: Send> fmt::Debug for FairConsumer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FairConsumer")
+            .field("queue", &self.queue)
+            .field("handles", &Arc::strong_count(&self.tickets))
+            .finish()
+    }
+}
+
+impl<T> Drop for FairConsumer<T> {
+    fn drop(&mut self) {
+        // Release pairs with the Acquire in `consumer_count`/`send_checked`
+        self.queue.consumers.fetch_sub(1, Ordering::Release);
+    }
+}
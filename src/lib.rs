@@ -1,45 +1,247 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![cfg_attr(feature = "simd", feature(portable_simd))]
 
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
-use std::cell::UnsafeCell;
-use std::mem::MaybeUninit;
+extern crate alloc;
 
-#[cfg(feature = "simd")]
-use std::simd::{u64x4, Simd};
+use alloc::boxed::Box;
+use alloc::sync::{Arc, Weak};
+use alloc::vec::Vec;
+use core::sync::atomic::Ordering;
+use core::fmt;
+use core::mem::MaybeUninit;
+use core::ops::Deref;
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use seq::{advance, compare, depth, free_seq, next_seq, word, SeqWord};
+#[cfg(feature = "metrics")]
+use sync::AtomicU64;
+use sync::{AtomicSeq, AtomicUsize, UnsafeCell};
+#[cfg(feature = "std")]
+use core::future::poll_fn;
+#[cfg(feature = "std")]
+use wait::Registration;
+use wait::WaitList;
 
 #[cfg(feature = "simd")]
 pub mod simd_queue;
+#[cfg(feature = "simd")]
+pub mod simd32_queue;
+
+pub mod aligned;
+pub mod array_queue;
+#[cfg(feature = "std")]
+pub mod broadcast_queue;
+#[cfg(feature = "std")]
+pub mod buffered;
+pub mod byte_ring;
+#[cfg(feature = "std")]
+pub mod dedup_queue;
+#[cfg(feature = "std")]
+pub mod delay_queue;
+#[cfg(feature = "std")]
+pub mod elastic_queue;
+pub mod error;
+pub mod fair;
+#[cfg(feature = "std")]
+pub mod overflow_queue;
+pub mod packed_queue;
+pub mod prefetch;
+#[cfg(feature = "std")]
+pub mod rate_limited;
+#[cfg(feature = "std")]
+pub mod select;
+pub mod seq;
+#[cfg(feature = "std")]
+pub mod sharded_queue;
+#[cfg(target_has_atomic = "64")]
+pub mod stack;
+#[cfg(feature = "futures")]
+pub mod sink;
+mod sync;
+#[cfg(feature = "std")]
+pub mod ttl_queue;
+mod wait;
+pub mod wait_strategy;
+pub mod work_stealing;
 
-// Cache line size for padding
-const CACHE_LINE: usize = 64;
+#[cfg(all(feature = "perf", target_os = "linux"))]
+pub mod perf;
 
+#[cfg(feature = "validating")]
+pub mod validating;
+
+/// Cache-line size, in bytes, that slots and shared positions are aligned to.
+///
+/// 64 on most targets. Apple's M-series cores are given 128 since their
+/// hardware moves lines in 128-byte pairs, and s390x has 256-byte lines;
+/// padding to only 64 there still leaves neighbouring slots sharing a line.
+pub const CACHE_LINE: usize = if cfg!(target_arch = "s390x") {
+    256
+} else if cfg!(all(target_arch = "aarch64", target_vendor = "apple")) {
+    128
+} else {
+    64
+};
+
+/// A single ring buffer slot: a sequence number plus storage for one item.
+///
+/// Slots are only handed out by `MpmcQueue::into_raw_parts`. A slot holds an
+/// initialized item for position `pos` when `sequence() == seq::word(pos + 1)`.
 #[repr(align(64))] // Align to cache line to avoid false sharing
-struct Slot<T> {
-    sequence: AtomicUsize,
+#[cfg_attr(all(target_arch = "aarch64", target_vendor = "apple"), repr(align(128)))]
+#[cfg_attr(target_arch = "s390x", repr(align(256)))]
+pub struct Slot<T> {
+    sequence: AtomicSeq,
     data: UnsafeCell<MaybeUninit<T>>,
+    abandoned: UnsafeCell<bool>, // published without data, see `send_generated`
+    #[cfg(feature = "producer-tags")]
+    producer: UnsafeCell<usize>, // id of the Producer that wrote `data`
+}
+
+// Producer tag for items sent directly through the queue
+const NO_PRODUCER: usize = usize::MAX;
+
+// `mask` of a queue whose capacity is not a power of 2 (see `new_exact`),
+// which indexes slots with `%` instead; no real mask can have every bit set
+const NO_MASK: usize = usize::MAX;
+
+fn mask_for(capacity: usize) -> usize {
+    if capacity.is_power_of_two() {
+        capacity - 1
+    } else {
+        NO_MASK
+    }
 }
 
 impl<T> Slot<T> {
     fn new(seq: usize) -> Self {
         Self {
-            sequence: AtomicUsize::new(seq),
+            sequence: AtomicSeq::new(word(seq)),
             data: UnsafeCell::new(MaybeUninit::uninit()),
+            abandoned: UnsafeCell::new(false),
+            #[cfg(feature = "producer-tags")]
+            producer: UnsafeCell::new(NO_PRODUCER),
+        }
+    }
+    
+    // Records which producer wrote the slot; a no-op without `producer-tags`
+    #[inline]
+    fn set_producer(&self, _producer: usize) {
+        #[cfg(feature = "producer-tags")]
+        unsafe {
+            self.producer.with_mut(|producer| *producer = _producer);
+        }
+    }
+    
+    /// Returns the slot's sequence number, truncated to its low 32 bits
+    /// with the `compact-seq` feature; compare it with `seq::word(pos + 1)`.
+    pub fn sequence(&self) -> SeqWord {
+        self.sequence.load(Ordering::Acquire)
+    }
+    
+    /// Returns a raw pointer to the slot's storage.
+    pub fn data_ptr(&self) -> *mut MaybeUninit<T> {
+        self.data.with_mut(|data| data)
+    }
+    
+    /// Returns true if the slot was published without an item, which happens
+    /// when a `send_generated` closure panics. Its storage is uninitialized
+    /// even if `sequence()` marks it as holding data.
+    pub fn is_abandoned(&self) -> bool {
+        self.abandoned.with(|abandoned| unsafe { *abandoned })
+    }
+    
+    // Called by the consumer that claimed the slot: returns whether the slot
+    // carries no item, resetting the flag for the slot's next use
+    #[inline]
+    fn take_abandoned(&self) -> bool {
+        self.abandoned.with_mut(|abandoned| unsafe { core::mem::replace(&mut *abandoned, false) })
+    }
+    
+    // Moves the item out. The caller must own the slot and it must hold an
+    // item; its storage is uninitialized (and scrubbed, see `scrub`) after
+    #[inline]
+    unsafe fn take(&self) -> T {
+        self.data.with_mut(|data| unsafe {
+            let item = (*data).assume_init_read();
+            Self::scrub(data);
+            item
+        })
+    }
+    
+    // Drops the item in place, with the same requirements as `take`
+    #[inline]
+    unsafe fn discard(&self) {
+        self.data.with_mut(|data| unsafe {
+            (*data).assume_init_drop();
+            Self::scrub(data);
+        })
+    }
+    
+    // Under `secure`, zeroes an emptied slot's bytes so a moved-out secret
+    // does not linger until the next lap overwrites it. Volatile writes so
+    // the compiler cannot drop them as stores to dead memory; callers run
+    // this before releasing the slot, so no producer ever sees the old bytes
+    #[inline]
+    #[cfg_attr(not(feature = "secure"), allow(unused_variables))]
+    unsafe fn scrub(data: *mut MaybeUninit<T>) {
+        #[cfg(feature = "secure")]
+        {
+            let bytes = data.cast::<u8>();
+            for i in 0..core::mem::size_of::<T>() {
+                unsafe { bytes.add(i).write_volatile(0) };
+            }
+            core::sync::atomic::compiler_fence(Ordering::SeqCst);
         }
     }
 }
 
 // Separate cache lines for producer and consumer positions to avoid false sharing
 #[repr(align(64))]
+#[cfg_attr(all(target_arch = "aarch64", target_vendor = "apple"), repr(align(128)))]
+#[cfg_attr(target_arch = "s390x", repr(align(256)))]
 struct ProducerPos {
     head: AtomicUsize,
 }
 
 #[repr(align(64))]
+#[cfg_attr(all(target_arch = "aarch64", target_vendor = "apple"), repr(align(128)))]
+#[cfg_attr(target_arch = "s390x", repr(align(256)))]
 struct ConsumerPos {
     tail: AtomicUsize,
 }
 
+// Lifetime totals behind the `metrics` feature, on their own cache line so
+// counting does not contend with head and tail
+#[cfg(feature = "metrics")]
+#[derive(Default)]
+#[repr(align(64))]
+#[cfg_attr(all(target_arch = "aarch64", target_vendor = "apple"), repr(align(128)))]
+#[cfg_attr(target_arch = "s390x", repr(align(256)))]
+struct Metrics {
+    sent: AtomicU64,
+    received: AtomicU64,
+    send_failures: AtomicU64,
+    high_water_mark: AtomicUsize, // largest len() seen after a send
+}
+
+/// What `send` does with an item that finds the queue full, chosen with
+/// `MpmcQueue::with_policy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OnFull {
+    /// Hand the item back in `Err`.
+    #[default]
+    Error,
+    /// Park until a consumer frees a slot, as `send_blocking` does.
+    #[cfg(feature = "std")]
+    Block,
+    /// Drop the item being sent and report success.
+    DropNewest,
+    /// Evict the oldest item to make room, as `send_overwrite` does.
+    DropOldest,
+}
+
 /// A high-performance bounded MPMC queue based on a ring buffer with sequence numbers.
 /// 
 /// This implementation is inspired by:
@@ -52,26 +254,144 @@ struct ConsumerPos {
 /// - No artificial retry limits or spin loops
 /// - Cache-line optimized to minimize false sharing
 /// - Memory-safe with proper ordering guarantees
+///
+/// Zero-sized items such as `()` need no special handling: availability is
+/// tracked by each slot's sequence number, not by its storage, so a queue of
+/// signals has the same capacity, ordering and drop behaviour as any other.
 pub struct MpmcQueue<T> {
     buffer: Box<[Slot<T>]>,
     capacity: usize,
-    mask: usize, // capacity - 1, for fast modulo via bitwise AND
+    mask: usize, // capacity - 1, for fast modulo via bitwise AND, or NO_MASK
     producer_pos: ProducerPos,
     consumer_pos: ConsumerPos,
+    wait_strategy: Box<dyn WaitStrategy>, // how contended retries wait
+    on_full: OnFull, // what `send` does when the queue is full
+    producers: AtomicUsize, // live Producer handles
+    consumers: AtomicUsize, // live Consumer and ExclusiveConsumer handles
+    not_empty: WaitList, // consumers parked in recv_blocking
+    not_full: WaitList, // producers parked in send_blocking
+    drained: WaitList, // waiters in await_completion
+    #[cfg(feature = "producer-tags")]
+    next_producer_id: AtomicUsize,
+    #[cfg(feature = "metrics")]
+    metrics: Metrics,
 }
 
 impl<T: Send> MpmcQueue<T> {
     /// Creates a new MPMC queue with the specified capacity.
     /// 
     /// The capacity must be a power of 2 for optimal performance.
-    /// If not, it will be rounded up to the next power of 2.
+    /// If not, it will be rounded up to the next power of 2 (and to at
+    /// least 2, since a single slot cannot tell "written" apart from "free
+    /// for the next lap"). Use `new_exact` to keep the requested size.
     pub fn new(capacity: usize) -> Self {
-        assert!(capacity > 0, "Capacity must be greater than 0");
-        
-        // Round up to next power of 2 for efficient masking
-        let capacity = capacity.next_power_of_two();
-        let mask = capacity - 1;
-        
+        Self::with_wait_strategy(capacity, Backoff::default())
+    }
+    
+    /// Like `new`, but returns an error instead of panicking when the
+    /// capacity is 0 or too large to allocate once rounded up.
+    /// 
+    /// For capacities taken from configuration or user input, where a bad
+    /// value should be reported rather than abort the process.
+    pub fn try_new(capacity: usize) -> Result<Self, CapacityError> {
+        let capacity = Self::rounded_capacity(capacity)?;
+        Ok(Self::with_slots(capacity, Box::new(Backoff::default())))
+    }
+    
+    /// Creates a queue with exactly `capacity` slots, without rounding up.
+    /// 
+    /// For budgeting memory precisely: `new(1000)` allocates 1024 slots,
+    /// `new_exact(1000)` allocates 1000. The tradeoff is speed. A power-of-2
+    /// ring maps a position to its slot with a bitwise AND; any other size
+    /// needs an integer division (`pos % capacity`) on every slot access,
+    /// which costs a few nanoseconds per operation. A power-of-2 `capacity`
+    /// gives the same queue as `new`.
+    /// 
+    /// Only available on 64-bit targets: positions wrap at `usize::MAX`, and
+    /// with a size that does not divide 2^64 the slot index jumps at the
+    /// wrap. That takes 2^64 operations on 64-bit targets, but only 2^32 on
+    /// 32-bit ones.
+    /// 
+    /// Panics if `capacity` is less than 2 or more than `seq::MAX_CAPACITY`.
+    #[cfg(target_pointer_width = "64")]
+    pub fn new_exact(capacity: usize) -> Self {
+        assert!(capacity >= 2, "Exact capacity must be at least 2");
+        assert!(capacity <= seq::MAX_CAPACITY, "Exact capacity is too large for the sequence numbers");
+        Self::with_slots(capacity, Box::new(Backoff::default()))
+    }
+    
+    /// Creates a queue whose contended retries wait according to `strategy`.
+    /// 
+    /// `new` uses exponential `Backoff`; `Backoff::with_ceiling` tunes how
+    /// long it spins before yielding. `BusySpin` gives the lowest latency
+    /// when every thread has its own core. When threads outnumber cores, a
+    /// spinning thread can keep the one it is waiting for (preempted
+    /// halfway through an operation) from running; `Yielding` or `Sleeping`
+    /// avoid that.
+    /// Capacity is rounded up as in `new`.
+    pub fn with_wait_strategy(capacity: usize, strategy: impl WaitStrategy + 'static) -> Self {
+        let capacity = Self::rounded_capacity(capacity).unwrap_or_else(|err| panic!("{err}"));
+        Self::with_slots(capacity, Box::new(strategy))
+    }
+    
+    /// Creates a queue whose `send` handles a full queue according to
+    /// `policy`.
+    /// 
+    /// The policy applies to the plain `send` of the queue and of its
+    /// `Producer` handles, so call sites need no full-handling of their
+    /// own. Operations that spell out their behaviour, such as
+    /// `send_blocking`, `send_timeout`, `send_overwrite`, `send_checked`,
+    /// `send_tracked` and the batch sends, ignore it. Capacity is rounded up
+    /// as in `new`.
+    pub fn with_policy(capacity: usize, policy: OnFull) -> Self {
+        let mut queue = Self::new(capacity);
+        queue.on_full = policy;
+        queue
+    }
+    
+    /// Returns the policy `send` applies when the queue is full.
+    pub fn policy(&self) -> OnFull {
+        self.on_full
+    }
+    
+    /// Creates a queue whose sends wake parked consumers in the order
+    /// `policy` gives.
+    /// 
+    /// Applies to consumers blocked in `recv_blocking` and the other
+    /// blocking or async receives; producers parked on a full queue are
+    /// always woken in FIFO order. `new` uses `WakePolicy::Fifo`. Capacity
+    /// is rounded up as in `new`.
+    #[cfg(feature = "std")]
+    pub fn with_wake_policy(capacity: usize, policy: WakePolicy) -> Self {
+        let mut queue = Self::new(capacity);
+        queue.not_empty = WaitList::with_policy(policy);
+        queue
+    }
+    
+    /// Returns the order in which sends wake parked consumers.
+    #[cfg(feature = "std")]
+    pub fn wake_policy(&self) -> WakePolicy {
+        self.not_empty.policy()
+    }
+    
+    // Rounds up to the next power of 2 for efficient masking, checking that
+    // the ring can be allocated
+    fn rounded_capacity(capacity: usize) -> Result<usize, CapacityError> {
+        if capacity == 0 {
+            return Err(CapacityError::Zero);
+        }
+        let capacity = capacity
+            .checked_next_power_of_two()
+            .filter(|&capacity| capacity <= seq::MAX_CAPACITY)
+            .ok_or(CapacityError::TooLarge)?
+            .max(2);
+        match capacity.checked_mul(core::mem::size_of::<Slot<T>>()) {
+            Some(bytes) if bytes <= isize::MAX as usize => Ok(capacity),
+            _ => Err(CapacityError::TooLarge),
+        }
+    }
+    
+    fn with_slots(capacity: usize, wait_strategy: Box<dyn WaitStrategy>) -> Self {
         // Initialize buffer with sequence numbers
         let mut buffer = Vec::with_capacity(capacity);
         for i in 0..capacity {
@@ -81,13 +401,24 @@ impl<T: Send> MpmcQueue<T> {
         Self {
             buffer: buffer.into_boxed_slice(),
             capacity,
-            mask,
+            mask: mask_for(capacity),
             producer_pos: ProducerPos {
                 head: AtomicUsize::new(0),
             },
             consumer_pos: ConsumerPos {
                 tail: AtomicUsize::new(0),
             },
+            wait_strategy,
+            on_full: OnFull::Error,
+            producers: AtomicUsize::new(0),
+            consumers: AtomicUsize::new(0),
+            not_empty: WaitList::new(),
+            not_full: WaitList::new(),
+            drained: WaitList::new(),
+            #[cfg(feature = "producer-tags")]
+            next_producer_id: AtomicUsize::new(0),
+            #[cfg(feature = "metrics")]
+            metrics: Metrics::default(),
         }
     }
     
@@ -95,267 +426,1956 @@ impl<T: Send> MpmcQueue<T> {
     /// 
     /// This is a wait-free operation that will either succeed immediately
     /// or fail if the queue is full. No artificial retry limits.
+    /// 
+    /// A queue built with `with_policy` applies its `OnFull` policy instead
+    /// of failing.
     pub fn send(&self, item: T) -> Result<(), T> {
+        self.send_with_policy(item, |item| self.send_now(item))
+    }
+    
+    // `send` without the `OnFull` policy
+    fn send_now(&self, item: T) -> Result<(), T> {
+        self.send_tagging(item, |slot| slot.set_producer(NO_PRODUCER)).map(|_| ())
+    }
+    
+    // Tries `send` once, then applies the `OnFull` policy if it failed
+    fn send_with_policy(&self, item: T, send: impl Fn(T) -> Result<(), T>) -> Result<(), T> {
+        let item = match send(item) {
+            Ok(()) => return Ok(()),
+            Err(item) => item,
+        };
+        match self.on_full {
+            OnFull::Error => Err(item),
+            #[cfg(feature = "std")]
+            OnFull::Block => self.send_parked(item, None, send),
+            OnFull::DropNewest => Ok(()),
+            OnFull::DropOldest => {
+                self.evict_until_sent(item, send, drop);
+                Ok(())
+            }
+        }
+    }
+    
+    /// Sends an item and returns a token that reports when a consumer has
+    /// received it, e.g. to advance a committed offset in an at-least-once
+    /// pipeline.
+    /// 
+    /// Returns the item back if the queue is full, like `send`. The token
+    /// watches the item's slot: once a consumer has taken the item and
+    /// handed the slot back, the slot's sequence number moves past the
+    /// item's lap and stays there. Checking costs one atomic load and adds
+    /// nothing to the send or receive paths. An item removed by
+    /// `send_overwrite` to make room counts as received.
+    pub fn send_tracked(&self, item: T) -> Result<AckToken<'_, T>, T> {
+        let pos = self.send_tagging(item, |slot| slot.set_producer(NO_PRODUCER))?;
+        Ok(AckToken { queue: self, pos })
+    }
+    
+    /// Sends an item, overwriting the oldest item if the queue is full.
+    /// 
+    /// Never fails: when the queue is full the oldest item is received to
+    /// make room, and the send is retried. The eviction goes through the
+    /// same claim as `recv`, so it is safe against consumers advancing the
+    /// tail concurrently; if one of them frees a slot first, no item is
+    /// evicted. Returns the evicted item, if any, so callers can account
+    /// for drops.
+    /// 
+    /// When other producers keep refilling the freed slot, one call may
+    /// have to evict more than once; only the last evicted item is
+    /// returned, the earlier ones are dropped.
+    pub fn send_overwrite(&self, item: T) -> Option<T> {
+        let mut evicted = None;
+        self.send_evicting(item, |oldest| evicted = Some(oldest));
+        evicted
+    }
+    
+    // `send_overwrite`, handing every evicted item to `evict`
+    pub(crate) fn send_evicting(&self, item: T, evict: impl FnMut(T)) {
+        self.evict_until_sent(item, |item| self.send_now(item), evict)
+    }
+    
+    // Retries `send`, evicting the oldest item before each retry
+    fn evict_until_sent(&self, mut item: T, send: impl Fn(T) -> Result<(), T>, mut evict: impl FnMut(T)) {
+        loop {
+            match send(item) {
+                Ok(()) => return,
+                Err(rejected) => {
+                    item = rejected;
+                    // If a consumer got there first, the retry finds the
+                    // freed slot
+                    if let Some(oldest) = self.recv() {
+                        evict(oldest);
+                    }
+                }
+            }
+        }
+    }
+    
+    /// Sends an item, parking the calling thread until there is room for it.
+    /// 
+    /// Every slot a consumer frees wakes one parked producer, so when many
+    /// producers are blocked and a single slot frees up, one of them claims
+    /// it and the rest stay parked.
+    #[cfg(feature = "std")]
+    pub fn send_blocking(&self, item: T) {
+        let sent = self.send_parked(item, None, |item| self.send_now(item));
+        debug_assert!(sent.is_ok());
+    }
+    
+    /// Sends an item, parking for at most `timeout` until there is room.
+    /// 
+    /// On timeout the item is handed back in `SendTimeoutError::Timeout`.
+    #[cfg(feature = "std")]
+    pub fn send_timeout(&self, item: T, timeout: Duration) -> Result<(), SendTimeoutError<T>> {
+        self.send_parked(item, Instant::now().checked_add(timeout), |item| self.send_now(item))
+            .map_err(SendTimeoutError::Timeout)
+    }
+    
+    /// Sends an item, parking until there is room or `deadline` passes.
+    /// 
+    /// The deadline is checked on every attempt, so it holds even when other
+    /// producers keep winning the freed slots.
+    #[cfg(feature = "std")]
+    pub fn send_deadline(&self, item: T, deadline: Instant) -> Result<(), SendTimeoutError<T>> {
+        self.send_parked(item, Some(deadline), |item| self.send_now(item))
+            .map_err(SendTimeoutError::Timeout)
+    }
+    
+    /// Sends an item, waiting asynchronously until there is room for it.
+    /// 
+    /// The task is woken when a consumer frees a slot, the same way
+    /// `send_blocking` is. Dropping the future before it completes hands
+    /// its place in line to the next waiting producer; the item is dropped.
+    #[cfg(feature = "std")]
+    pub async fn send_async(&self, item: T) {
+        self.send_polled(item, |item| self.send_now(item)).await
+    }
+    
+    // Async counterpart of `send_parked`: retries `send` until it succeeds,
+    // waiting on `not_full` in between
+    #[cfg(feature = "std")]
+    async fn send_polled(&self, item: T, send: impl Fn(T) -> Result<(), T>) {
+        let mut item = Some(item);
+        let mut waiter = Registration::new(&self.not_full);
+        poll_fn(|cx| {
+            waiter.poll_until(cx, || match send(item.take()?) {
+                Ok(()) => Some(()),
+                Err(rejected) => {
+                    item = Some(rejected);
+                    None
+                }
+            })
+        })
+        .await
+    }
+    
+    // Retries `send` until it succeeds or `deadline` passes, parking on
+    // `not_full` in between. Hands the item back on timeout.
+    #[cfg(feature = "std")]
+    fn send_parked(
+        &self,
+        item: T,
+        deadline: Option<Instant>,
+        send: impl Fn(T) -> Result<(), T>,
+    ) -> Result<(), T> {
+        let mut item = Some(item);
+        let sent = self.not_full.wait_until_deadline(deadline, || match send(item.take()?) {
+            Ok(()) => Some(()),
+            Err(rejected) => {
+                item = Some(rejected);
+                None
+            }
+        });
+        match (sent, item) {
+            (Some(()), _) => Ok(()),
+            (None, Some(item)) => Err(item),
+            (None, None) => unreachable!("a failed send hands the item back"),
+        }
+    }
+    
+    /// `send`, with `fill` run on the claimed slot before the item is
+    /// published. Returns the position the item was sent at.
+    #[inline]
+    // Memory ordering, here and in `recv_with` (the scheme of Vyukov's
+    // bounded MPMC queue): all synchronization of slot contents goes through
+    // each slot's `sequence`. A producer publishes with a Release store
+    // after writing the item, and the consumer's Acquire load that sees it
+    // makes the item visible; the consumer's Release store that frees the
+    // slot, seen by the next lap's producer with Acquire, orders the read
+    // before the overwrite. `head` and `tail` only hand out positions: the
+    // CAS's atomicity gives each position to exactly one thread, whatever
+    // its ordering, and a stale value only makes the CAS fail. So they stay
+    // Relaxed on all platforms, including weakly ordered ones like AArch64.
+    // The one exception is a slot found ahead of the position: the position
+    // is then known to be stale, and it is re-read with Acquire until it
+    // moves, so the retry never starts from the same stale value.
+    fn send_tagging(&self, item: T, fill: impl FnOnce(&Slot<T>)) -> Result<usize, T> {
+        let mut attempt = 0u32;
         loop {
-            // Get the current producer position
+            // Relaxed: a stale head only leads to a mismatch or a failed CAS
             let head = self.producer_pos.head.load(Ordering::Relaxed);
-            let slot = &self.buffer[head & self.mask];
+            let slot = &self.buffer[self.index(head)];
             
-            // Check the slot's sequence number
+            // Acquire: pairs with the Release that freed the slot, so the
+            // last lap's read of it happens before our write
             let seq = slot.sequence.load(Ordering::Acquire);
             let expected_seq = head;
             
-            match seq.cmp(&expected_seq) {
-                std::cmp::Ordering::Equal => {
-                    // Slot is available, try to claim it
+            match compare(seq, expected_seq) {
+                core::cmp::Ordering::Equal => {
+                    // Slot is available, try to claim it. Relaxed: the CAS
+                    // only decides ownership, the Acquire above already
+                    // ordered the previous read
                     match self.producer_pos.head.compare_exchange_weak(
                         head,
-                        head.wrapping_add(1),
+                        next_seq(head),
                         Ordering::Relaxed,
                         Ordering::Relaxed,
                     ) {
                         Ok(_) => {
                             // Successfully claimed the slot, now store the data
+                            fill(slot);
                             unsafe {
-                                (*slot.data.get()).write(item);
+                                slot.data.with_mut(|data| (*data).write(item));
                             }
                             
-                            // Signal that data is ready by advancing sequence
-                            slot.sequence.store(expected_seq.wrapping_add(1), Ordering::Release);
-                            return Ok(());
+                            // Release: publishes the item (and tag) to the
+                            // consumer's Acquire load of the sequence
+                            slot.sequence.store(word(next_seq(expected_seq)), Ordering::Release);
+                            self.count_sent(1);
+                            self.not_empty.notify_one();
+                            return Ok(head);
                         }
                         Err(_) => {
                             // Another producer claimed this slot, retry
-                            std::hint::spin_loop();
+                            self.retry_wait(&mut attempt);
                             continue;
                         }
                     }
                 }
-                std::cmp::Ordering::Less => {
+                core::cmp::Ordering::Less => {
                     // Slot is behind, queue might be full
-                    // Check if we've wrapped around (queue is full)
+                    // Check if we've wrapped around (queue is full). The
+                    // slot still holding last lap's item means the queue was
+                    // full when `seq` was read; tail is only a heuristic for
+                    // telling that apart from a consumer mid-read, and reads
+                    // no data, so its ordering does not matter
                     let tail = self.consumer_pos.tail.load(Ordering::Acquire);
-                    if head.wrapping_sub(tail) >= self.capacity {
+                    if depth(head, tail) >= self.capacity {
+                        self.count_send_failure();
                         return Err(item); // Queue is full
                     }
                     // Otherwise, retry with updated head
-                    std::hint::spin_loop();
+                    self.retry_wait(&mut attempt);
                     continue;
                 }
-                std::cmp::Ordering::Greater => {
-                    // Slot is ahead, another producer is working on it
-                    // This shouldn't happen in normal operation, but handle gracefully
-                    std::hint::spin_loop();
+                core::cmp::Ordering::Greater => {
+                    // Slot is ahead: another producer already claimed `head`,
+                    // so wait until we see head past it
+                    while self.producer_pos.head.load(Ordering::Acquire) == head {
+                        self.retry_wait(&mut attempt);
+                    }
                     continue;
                 }
             }
         }
     }
     
-    /// Attempts to receive an item from the queue.
+    /// Claims up to `count` contiguous slots and fills each one with
+    /// `generate(position)`, returning how many items were sent.
     /// 
-    /// This is a wait-free operation that will either succeed immediately
-    /// or return None if the queue is empty.
-    pub fn recv(&self) -> Option<T> {
-        loop {
-            // Get the current consumer position
-            let tail = self.consumer_pos.tail.load(Ordering::Relaxed);
-            let slot = &self.buffer[tail & self.mask];
-            
-            // Check the slot's sequence number
-            let seq = slot.sequence.load(Ordering::Acquire);
-            let expected_seq = tail.wrapping_add(1);
-            
-            match seq.cmp(&expected_seq) {
-                std::cmp::Ordering::Equal => {
-                    // Data is available, try to claim it
-                    match self.consumer_pos.tail.compare_exchange_weak(
-                        tail,
-                        tail.wrapping_add(1),
-                        Ordering::Relaxed,
-                        Ordering::Relaxed,
-                    ) {
-                        Ok(_) => {
-                            // Successfully claimed the slot, read the data
-                            let item = unsafe { (*slot.data.get()).assume_init_read() };
-                            
-                            // Mark slot as available for producers
-                            slot.sequence.store(
-                                tail.wrapping_add(self.capacity),
-                                Ordering::Release,
-                            );
-                            return Some(item);
-                        }
-                        Err(_) => {
-                            // Another consumer claimed this slot, retry
-                            std::hint::spin_loop();
-                            continue;
-                        }
-                    }
-                }
-                std::cmp::Ordering::Less => {
-                    // No data available, queue is empty
-                    return None;
-                }
-                std::cmp::Ordering::Greater => {
-                    // Slot is ahead, shouldn't happen in normal operation
-                    std::hint::spin_loop();
-                    continue;
-                }
-            }
-        }
+    /// `position` is the item's logical sequence number: the queue position
+    /// its slot was claimed at, i.e. the number of items sent through the
+    /// queue before it (wrapping at `usize::MAX`). The claimed positions are
+    /// consecutive, so a batch is generated with `position`, `position + 1`,
+    /// ... and consumers receive the items in that order relative to each
+    /// other. `generate` is called once per claimed slot, in order, and only
+    /// after the slots are claimed, so no input buffer is needed. Fewer than
+    /// `count` items (possibly 0) are sent if the queue does not have room.
+    /// 
+    /// If `generate` panics, the items already generated stay in the queue
+    /// and the rest of the claimed slots are published as abandoned: consumers skip
+    /// them without reading, so the queue stays usable. Abandoned slots count
+    /// towards `len()` until a consumer passes them. The panic is propagated.
+    pub fn send_generated<F: FnMut(usize) -> T>(&self, count: usize, mut generate: F) -> usize {
+        self.send_run(count, |_, pos| generate(pos))
     }
     
-    /// Returns the capacity of the queue.
-    pub fn capacity(&self) -> usize {
-        self.capacity
+    /// Constructs an item in place: claims a slot and lets `init` write the
+    /// item straight into it, then publishes it.
+    /// 
+    /// This avoids building a large `T` on the stack and moving it into the
+    /// queue. Returns `Err(init)` without calling it if the queue is full.
+    /// If `init` panics, the slot is published as abandoned (see
+    /// `send_generated`), so the queue is not wedged; the panic is propagated.
+    /// 
+    /// # Safety
+    /// 
+    /// `init` must fully initialize the `MaybeUninit` before returning
+    /// normally: consumers read it as a `T`.
+    pub unsafe fn send_with<F: FnOnce(&mut MaybeUninit<T>)>(&self, init: F) -> Result<(), F> {
+        let (head, claimed) = self.claim_run(1);
+        if claimed == 0 {
+            return Err(init);
+        }
+        
+        let mut guard = Abandon {
+            queue: self,
+            next: head,
+            end: next_seq(head),
+            multiple: false,
+        };
+        let slot = &self.buffer[self.index(head)];
+        slot.set_producer(NO_PRODUCER);
+        // The slot is ours until it is published below
+        slot.data.with_mut(|data| init(unsafe { &mut *data }));
+        slot.sequence.store(word(next_seq(head)), Ordering::Release);
+        self.count_sent(1);
+        guard.next = guard.end;
+        Ok(())
     }
     
-    /// Returns true if the queue is empty.
+    /// Sends a slice of items, claiming runs of contiguous slots with a single
+    /// `compare_exchange` on the head instead of one per item.
     /// 
-    /// Note: This is a snapshot view and may change immediately after the call.
-    pub fn is_empty(&self) -> bool {
-        let head = self.producer_pos.head.load(Ordering::Acquire);
-        let tail = self.consumer_pos.tail.load(Ordering::Acquire);
-        head == tail
+    /// Returns `Ok(items.len())` when everything was sent, or `Err(sent)` if
+    /// the queue filled up after the first `sent` items (possibly 0). Items
+    /// are cloned into the queue and keep their relative order. A run that
+    /// crosses the end of the ring buffer needs no special handling: slots
+    /// are addressed by position, so it is claimed and filled like any other.
+    /// When only part of the requested run is free (a consumer has not
+    /// released the next slot yet), the free part is sent and the rest is
+    /// claimed in a further run.
+    pub fn send_slice(&self, items: &[T]) -> Result<usize, usize>
+    where
+        T: Clone,
+    {
+        let mut sent = 0;
+        while sent < items.len() {
+            let rest = &items[sent..];
+            let claimed = self.send_run(rest.len(), |offset, _| rest[offset].clone());
+            if claimed == 0 {
+                return Err(sent);
+            }
+            sent += claimed;
+        }
+        Ok(sent)
     }
     
-    /// Returns true if the queue is full.
+    /// Sends items from an exact-size iterator, claiming contiguous runs of
+    /// slots like `send_slice`.
     /// 
-    /// Note: This is a snapshot view and may change immediately after the call.
-    pub fn is_full(&self) -> bool {
-        let head = self.producer_pos.head.load(Ordering::Acquire);
-        let tail = self.consumer_pos.tail.load(Ordering::Acquire);
-        head.wrapping_sub(tail) >= self.capacity
+    /// Items are only pulled from the iterator once their slots are claimed,
+    /// so when the queue fills up the unsent items are still in the iterator:
+    /// pass `&mut iter` to keep them. Returns `Ok(n)` once the iterator is
+    /// exhausted, or `Err(sent)` if the queue filled up first.
+    /// 
+    /// The claim size comes from `ExactSizeIterator::len`. An iterator that
+    /// yields fewer items than it reported makes this panic, leaving the
+    /// claimed but unfilled slots abandoned as described for `send_generated`.
+    pub fn send_iter<I>(&self, items: I) -> Result<usize, usize>
+    where
+        I: IntoIterator<Item = T>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let mut items = items.into_iter();
+        let mut sent = 0;
+        while items.len() > 0 {
+            let claimed = self.send_run(items.len(), |_, _| {
+                items.next().expect("ExactSizeIterator yielded fewer items than its len()")
+            });
+            if claimed == 0 {
+                return Err(sent);
+            }
+            sent += claimed;
+        }
+        Ok(sent)
     }
     
-    /// Returns the approximate number of items in the queue.
+    /// Sends items by value, handing back the ones that did not fit.
     /// 
-    /// Note: This is a snapshot view and may change immediately after the call.
-    pub fn len(&self) -> usize {
-        let head = self.producer_pos.head.load(Ordering::Acquire);
-        let tail = self.consumer_pos.tail.load(Ordering::Acquire);
-        head.wrapping_sub(tail)
+    /// The move-only counterpart of `send_slice`: items are claimed in
+    /// contiguous runs the same way, including runs that wrap around the
+    /// end of the ring, but moved into the queue instead of cloned. Returns
+    /// `Err(rest)` with the unsent items, in order, if the queue filled up
+    /// first, so they can be retried later as they are.
+    pub fn send_all(&self, items: Vec<T>) -> Result<(), Vec<T>> {
+        let mut items = items.into_iter();
+        match self.send_iter(&mut items) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(items.collect()),
+        }
     }
     
-}
-
-// Separate impl block without Send bound for Drop implementation
-impl<T> MpmcQueue<T> {
-    /// Internal method to check if queue is empty without Send bound requirement
-    fn is_empty_unchecked(&self) -> bool {
-        let head = self.producer_pos.head.load(Ordering::Relaxed);
-        let tail = self.consumer_pos.tail.load(Ordering::Relaxed);
-        head == tail
-    }
-}
-
-impl<T> Drop for MpmcQueue<T> {
-    fn drop(&mut self) {
-        // Drain any remaining items to prevent memory leaks
-        // We need to manually drain since recv() requires T: Send
-        while !self.is_empty_unchecked() {
-            let tail = self.consumer_pos.tail.load(Ordering::Relaxed);
-            let slot = &self.buffer[tail & self.mask];
-            let seq = slot.sequence.load(Ordering::Acquire);
+    /// Sends items from any iterator until it is exhausted or the queue is
+    /// full.
+    /// 
+    /// Returns `Err(rest)` if the queue filled up first, where `rest` yields
+    /// the unsent items in order: possibly one item that was already pulled
+    /// from the iterator, then the rest of the iterator. While the
+    /// iterator's `size_hint` promises more items, that many slots are
+    /// claimed in one run as in `send_iter`; an iterator that yields fewer
+    /// items than its lower bound makes this panic.
+    pub fn try_extend<I: IntoIterator<Item = T>>(
+        &self,
+        items: I,
+    ) -> Result<(), core::iter::Chain<core::option::IntoIter<T>, I::IntoIter>> {
+        let mut items = items.into_iter();
+        loop {
+            let promised = items.size_hint().0;
+            if promised > 0 {
+                let claimed = self.send_run(promised, |_, _| {
+                    items.next().expect("iterator yielded fewer items than its size_hint")
+                });
+                if claimed == 0 {
+                    return Err(None.into_iter().chain(items));
+                }
+                continue;
+            }
             
-            if seq == tail.wrapping_add(1) {
-                // Data is available
-                if self.consumer_pos.tail.compare_exchange_weak(
-                    tail,
-                    tail.wrapping_add(1),
-                    Ordering::Relaxed,
-                    Ordering::Relaxed,
-                ).is_ok() {
-                    // Successfully claimed the slot, drop the data
-                    unsafe {
-                        (*slot.data.get()).assume_init_drop();
+            match items.next() {
+                None => return Ok(()),
+                Some(item) => {
+                    if let Err(item) = self.send_now(item) {
+                        return Err(Some(item).into_iter().chain(items));
                     }
-                    // Mark slot as available for producers
-                    slot.sequence.store(
-                        tail.wrapping_add(self.capacity),
-                        Ordering::Release,
-                    );
                 }
-            } else {
-                break; // No more data or inconsistent state
             }
         }
     }
-}
-
-unsafe impl<T: Send> Send for MpmcQueue<T> {}
-unsafe impl<T: Send> Sync for MpmcQueue<T> {}
-
-/// A producer handle for the MPMC queue.
-/// 
-/// Multiple producers can send items concurrently.
-pub struct Producer<T> {
-    queue: Arc<MpmcQueue<T>>,
-}
-
-impl<T: Send> Producer<T> {
-    pub fn new(queue: Arc<MpmcQueue<T>>) -> Self {
-        Self { queue }
-    }
-    
-    /// Sends an item to the queue.
-    /// 
-    /// This is now a synchronous, wait-free operation.
-    pub fn send(&self, item: T) -> Result<(), T> {
-        self.queue.send(item)
-    }
     
-    /// Async version of send for compatibility with existing code.
-    pub async fn send_async(&self, item: T) -> Result<(), T> {
-        // Since the new implementation is wait-free, we can call it directly
-        // without spawn_blocking
-        self.send(item)
+    // Claims up to `count` contiguous free slots with a single CAS on head and
+    // fills them with `generate(offset, position)`. Returns the number claimed
+    // (0 only if the queue is full or `count` is 0).
+    fn send_run(&self, count: usize, mut generate: impl FnMut(usize, usize) -> T) -> usize {
+        let (head, claimed) = self.claim_run(count);
+        if claimed == 0 {
+            return 0;
+        }
+        
+        // Also wakes consumers for the batch once it is published
+        let mut guard = Abandon {
+            queue: self,
+            next: head,
+            end: advance(head, claimed),
+            multiple: claimed > 1,
+        };
+        while guard.next != guard.end {
+            let pos = guard.next;
+            let item = generate(depth(pos, head), pos);
+            self.publish_claimed(pos, item);
+            guard.next = next_seq(pos);
+        }
+        claimed
     }
     
-    /// Returns true if the queue is full.
-    pub fn is_full(&self) -> bool {
-        self.queue.is_full()
+    // Writes and publishes the item for `pos`, a slot claimed by `claim_run`
+    fn publish_claimed(&self, pos: usize, item: T) {
+        let slot = &self.buffer[self.index(pos)];
+        slot.set_producer(NO_PRODUCER);
+        unsafe {
+            slot.data.with_mut(|data| (*data).write(item));
+        }
+        slot.sequence.store(word(next_seq(pos)), Ordering::Release);
+        self.count_sent(1);
     }
     
-    /// Returns the capacity of the queue.
-    pub fn capacity(&self) -> usize {
-        self.queue.capacity()
-    }
-}
-
-impl<T: Send> Clone for Producer<T> {
-    fn clone(&self) -> Self {
-        Self {
-            queue: Arc::clone(&self.queue),
+    /// Moves up to `max` items from `from` to `to`, oldest first, returning
+    /// how many moved.
+    /// 
+    /// Stops early once `from` is empty or `to` is full. Room in `to` is
+    /// claimed before items leave `from`, in runs with a single CAS on its
+    /// head, so no item is ever taken out without a slot to go to. If other
+    /// consumers drain `from` between the claim and the receive, the slots
+    /// left over are released as abandoned (see `send_generated`): they
+    /// count in `to.len()` until a consumer skips them.
+    pub fn transfer(from: &Self, to: &Self, max: usize) -> usize {
+        let mut moved = 0;
+        while moved < max {
+            let wanted = (max - moved).min(from.len());
+            if wanted == 0 {
+                break;
+            }
+            let (head, claimed) = to.claim_run(wanted);
+            if claimed == 0 {
+                break;
+            }
+            
+            let mut guard = Abandon {
+                queue: to,
+                next: head,
+                end: advance(head, claimed),
+                multiple: claimed > 1,
+            };
+            while guard.next != guard.end {
+                let Some(item) = from.recv() else {
+                    return moved;
+                };
+                to.publish_claimed(guard.next, item);
+                guard.next = next_seq(guard.next);
+                moved += 1;
+            }
         }
-    }
-}
-
-/// A consumer handle for the MPMC queue.
-/// 
-/// Multiple consumers can receive items concurrently.
-pub struct Consumer<T> {
-    queue: Arc<MpmcQueue<T>>,
-}
-
-impl<T: Send> Consumer<T> {
-    pub fn new(queue: Arc<MpmcQueue<T>>) -> Self {
-        Self { queue }
+        moved
     }
     
-    /// Receives an item from the queue.
+    /// Reserves `n` contiguous slots with a single CAS on the head, to be
+    /// filled at the caller's pace and published together by `commit`.
     /// 
-    /// This is now a synchronous, wait-free operation.
-    pub fn recv(&self) -> Option<T> {
+    /// Returns None if `n` slots are not free right now. Consumers see none
+    /// of the reserved items before `commit`, which publishes them in order.
+    /// The slots are cache-line padded rather than adjacent in memory, so
+    /// items are written through `WriteReservation::push` instead of a
+    /// `&mut [MaybeUninit<T>]`; a run that wraps around the end of the ring
+    /// needs no special handling. Dropping the reservation without
+    /// committing drops the pushed items and releases the slots as abandoned
+    /// (see `send_generated`), since other producers may already have
+    /// claimed the slots after it.
+    /// 
+    /// Consumers stop at the first reserved slot until it is published, so
+    /// hold reservations briefly.
+    pub fn reserve(&self, n: usize) -> Option<WriteReservation<'_, T>> {
+        let mut attempt = 0u32;
+        loop {
+            let head = self.producer_pos.head.load(Ordering::Relaxed);
+            if n > self.capacity {
+                self.count_send_failure();
+                return None;
+            }
+            
+            let free = (0..n).all(|i| {
+                let pos = advance(head, i);
+                self.buffer[self.index(pos)].sequence.load(Ordering::Acquire) == word(pos)
+            });
+            if !free {
+                // As in `claim_run`, only the tail tells a full queue apart
+                // from a stale head or a slot still being released; a depth
+                // past the capacity means the tail has overtaken a stale head
+                let tail = self.consumer_pos.tail.load(Ordering::Acquire);
+                let used = depth(head, tail);
+                if used <= self.capacity && used > self.capacity - n {
+                    self.count_send_failure();
+                    return None;
+                }
+                self.retry_wait(&mut attempt);
+                continue;
+            }
+            
+            if self.producer_pos.head.compare_exchange_weak(
+                head,
+                advance(head, n),
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ).is_ok() {
+                return Some(WriteReservation {
+                    queue: self,
+                    head,
+                    len: n,
+                    filled: 0,
+                });
+            }
+            self.retry_wait(&mut attempt);
+        }
+    }
+    
+    /// Claims up to `count` contiguous free slots with a single CAS on the
+    /// head, returning `(head, claimed)`; `claimed` is 0 if the queue is full.
+    fn claim_run(&self, count: usize) -> (usize, usize) {
+        let mut attempt = 0u32;
+        loop {
+            let head = self.producer_pos.head.load(Ordering::Relaxed);
+            
+            // Count the free slots from head, up to the requested batch size
+            let limit = count.min(self.capacity);
+            let mut free = 0;
+            while free < limit {
+                let pos = advance(head, free);
+                if self.buffer[self.index(pos)].sequence.load(Ordering::Acquire) != word(pos) {
+                    break;
+                }
+                free += 1;
+            }
+            
+            if free == 0 {
+                if count == 0 {
+                    return (head, 0);
+                }
+                let tail = self.consumer_pos.tail.load(Ordering::Acquire);
+                if depth(head, tail) >= self.capacity {
+                    self.count_send_failure();
+                    return (head, 0); // Queue is full
+                }
+                // Head is stale or a consumer is still releasing the slot
+                self.retry_wait(&mut attempt);
+                continue;
+            }
+            
+            // Free slots stay free until a producer claims them, so the
+            // whole run is ours if head has not moved
+            if self.producer_pos.head.compare_exchange_weak(
+                head,
+                advance(head, free),
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ).is_ok() {
+                return (head, free);
+            }
+            self.retry_wait(&mut attempt);
+        }
+    }
+    
+    /// Attempts to receive an item from the queue.
+    /// 
+    /// This is a wait-free operation that will either succeed immediately
+    /// or return None if the queue is empty.
+    pub fn recv(&self) -> Option<T> {
+        self.recv_with(|slot, _| unsafe { slot.take() })
+    }
+    
+    /// Receives an item together with the queue position it was sent at.
+    /// 
+    /// The position is the item's logical sequence number, as passed to
+    /// `send_generated`: the number of items sent through the queue before
+    /// it. Positions increase by one per item in send order, so consumers
+    /// receiving from several queues in a known order of sends can
+    /// merge-sort by position to restore it. Positions are per queue, and
+    /// wrap at `usize::MAX` like `producer_position`.
+    pub fn recv_seq(&self) -> Option<(T, usize)> {
+        self.recv_with(|slot, pos| (unsafe { slot.take() }, pos))
+    }
+    
+    /// Returns an iterator that receives items until the queue is empty.
+    /// 
+    /// The iterator borrows the queue, so `for item in queue.drain() { ... }`
+    /// works on a shared queue, e.g. at shutdown. It never waits: iteration
+    /// ends the first time `recv` finds no ready item. With producers still
+    /// running, items sent after that point stay in the queue, and iteration
+    /// can end while other producers are still writing slots they claimed.
+    /// Concurrent consumers are fine: slots claimed by another consumer are
+    /// simply not yielded here.
+    pub fn drain(&self) -> Drain<'_, T> {
+        Drain { queue: self }
+    }
+    
+    /// Receives and drops every item that is ready, returning how many were
+    /// removed.
+    /// 
+    /// Use it to flush stale items, e.g. on reconfiguration, while keeping
+    /// the queue and its handles. Like `drain`, it stops the first time the
+    /// queue is empty, so items sent concurrently may survive, and it is
+    /// safe to call alongside other producers and consumers.
+    pub fn clear(&self) -> usize {
+        self.drain().count()
+    }
+    
+    /// Receives up to `out.len()` items into `out`, returning how many were
+    /// written (to the front of `out`; the rest is left untouched).
+    /// 
+    /// Runs of ready slots are claimed with a single `compare_exchange` on the
+    /// tail instead of one per item. The values being overwritten in `out`
+    /// are dropped.
+    /// 
+    /// Ordering: each claimed run is a contiguous range of queue positions,
+    /// so the items written by one call are in send order and were not
+    /// received by anyone else. When several consumers call this
+    /// concurrently, each gets its own disjoint runs, and runs taken by
+    /// different consumers interleave arbitrarily, as with `recv`.
+    pub fn recv_slice(&self, out: &mut [T]) -> usize {
+        let mut written = 0;
+        self.recv_runs(out.len(), |item| {
+            out[written] = item;
+            written += 1;
+        })
+    }
+    
+    /// Appends up to `max` ready items to `out`, growing it as needed, and
+    /// returns how many were appended.
+    /// 
+    /// Stops at the first empty observation, like `recv_slice`, and claims
+    /// runs of ready slots the same way. Suits callers that accumulate
+    /// items across calls and would otherwise manage a scratch slice.
+    pub fn recv_many(&self, out: &mut Vec<T>, max: usize) -> usize {
+        out.reserve(max.min(self.len()));
+        self.recv_runs(max, |item| out.push(item))
+    }
+    
+    // Receives up to `max` ready items, claiming runs of them with a single
+    // CAS each, and hands them to `put` in order. Returns how many were
+    // received.
+    fn recv_runs(&self, max: usize, mut put: impl FnMut(T)) -> usize {
+        let mut received = 0;
+        while received < max {
+            let Some((tail, claimed)) = self.claim_ready_run(max - received) else {
+                break;
+            };
+            
+            // Releases the rest of the run (dropping its items) if `put`
+            // panics
+            struct Release<'a, T> {
+                queue: &'a MpmcQueue<T>,
+                next: usize,
+                end: usize,
+            }
+            
+            impl<T> Release<'_, T> {
+                fn take_next(&mut self) -> Option<T> {
+                    let queue = self.queue;
+                    let slot = &queue.buffer[queue.index(self.next)];
+                    let item = if slot.take_abandoned() {
+                        None
+                    } else {
+                        Some(unsafe { slot.take() })
+                    };
+                    slot.sequence.store(word(free_seq(self.next, queue.capacity)), Ordering::Release);
+                    self.next = next_seq(self.next);
+                    item
+                }
+            }
+            
+            impl<T> Drop for Release<'_, T> {
+                fn drop(&mut self) {
+                    while self.next != self.end {
+                        drop(self.take_next());
+                    }
+                    self.queue.not_full.notify_all();
+                    self.queue.notify_if_drained(self.end);
+                }
+            }
+            
+            let mut run = Release {
+                queue: self,
+                next: tail,
+                end: advance(tail, claimed),
+            };
+            while run.next != run.end {
+                if let Some(item) = run.take_next() {
+                    self.count_received(1);
+                    put(item);
+                    received += 1;
+                }
+            }
+        }
+        received
+    }
+    
+    // Claims the longest run of ready slots at the tail, up to `max`, with a
+    // single CAS. Returns the run's first position and length, or None if
+    // the queue is empty.
+    fn claim_ready_run(&self, max: usize) -> Option<(usize, usize)> {
+        let mut attempt = 0u32;
+        loop {
+            let tail = self.consumer_pos.tail.load(Ordering::Relaxed);
+            
+            let limit = max.min(self.capacity);
+            let mut ready = 0;
+            while ready < limit {
+                let pos = advance(tail, ready);
+                if self.buffer[self.index(pos)].sequence.load(Ordering::Acquire) != word(next_seq(pos)) {
+                    break;
+                }
+                ready += 1;
+            }
+            
+            if ready == 0 {
+                let seq = self.buffer[self.index(tail)].sequence.load(Ordering::Acquire);
+                if compare(seq, next_seq(tail)).is_lt() {
+                    return None; // No data available, queue is empty
+                }
+                // Tail is stale or the slot just became ready, retry
+                self.retry_wait(&mut attempt);
+                continue;
+            }
+            
+            // Ready slots stay ready until a consumer claims them, so the
+            // whole run is ours if tail has not moved
+            if self.consumer_pos.tail.compare_exchange_weak(
+                tail,
+                advance(tail, ready),
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ).is_ok() {
+                return Some((tail, ready));
+            }
+            self.retry_wait(&mut attempt);
+        }
+    }
+    
+    /// Receives an item, parking the calling thread until one is available.
+    /// 
+    /// Every successful send wakes a parked consumer, so this does not poll.
+    /// When several consumers are blocked, each item goes to exactly one of
+    /// them; the others (and any woken spuriously) go back to sleep.
+    #[cfg(feature = "std")]
+    pub fn recv_blocking(&self) -> T {
+        self.not_empty.wait_until(|| self.recv())
+    }
+    
+    /// Receives an item, parking for at most `timeout` until one is available.
+    /// 
+    /// Returns `Err(RecvTimeoutError::Timeout)` if nothing arrived in time.
+    #[cfg(feature = "std")]
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.recv_deadline(deadline),
+            // Too far in the future to represent, wait indefinitely
+            None => Ok(self.recv_blocking()),
+        }
+    }
+    
+    /// Receives an item, parking until one is available or `deadline` passes.
+    /// 
+    /// Spurious wakeups do not extend the wait: the remaining time is always
+    /// measured against `deadline`.
+    #[cfg(feature = "std")]
+    pub fn recv_deadline(&self, deadline: Instant) -> Result<T, RecvTimeoutError> {
+        self.not_empty
+            .wait_until_deadline(Some(deadline), || self.recv())
+            .ok_or(RecvTimeoutError::Timeout)
+    }
+    
+    /// Receives into `out` until it is full or `timeout` has elapsed,
+    /// returning how many items were written to the front of `out`.
+    /// 
+    /// This is micro-batching: gather up to `out.len()` items for one
+    /// downstream write, without waiting forever for a batch to fill. Ready
+    /// items are taken in runs as in `recv_slice`, and in between the thread
+    /// parks until the next item arrives. The deadline is fixed when the
+    /// call starts, so items trickling in one at a time do not extend it.
+    #[cfg(feature = "std")]
+    pub fn recv_batch_timeout(&self, out: &mut [T], timeout: Duration) -> usize {
+        let deadline = Instant::now().checked_add(timeout);
+        let mut received = 0;
+        while received < out.len() {
+            received += self.recv_slice(&mut out[received..]);
+            if received == out.len() {
+                break;
+            }
+            match self.not_empty.wait_until_deadline(deadline, || self.recv()) {
+                Some(item) => {
+                    out[received] = item;
+                    received += 1;
+                }
+                None => break,
+            }
+        }
+        received
+    }
+    
+    /// Receives an item, parking until one is available or no `Producer`
+    /// is left to send one.
+    /// 
+    /// Returns `Err(RecvError::Disconnected)` only when the queue is empty
+    /// and every `Producer` handle has been dropped, so items sent before
+    /// the last producer went away are still received. Dropping the last
+    /// producer wakes every consumer parked here.
+    /// 
+    /// As with `await_completion`, only `Producer` handles are counted: a
+    /// queue that never had one is disconnected as soon as it is empty, and
+    /// creating a new `Producer` afterwards reconnects it.
+    #[cfg(feature = "std")]
+    pub fn recv_disconnected(&self) -> Result<T, RecvError> {
+        self.not_empty.wait_until(|| match self.try_recv() {
+            Ok(item) => Some(Ok(item)),
+            Err(TryRecvError::Disconnected) => Some(Err(RecvError::Disconnected)),
+            Err(TryRecvError::Empty) => None,
+        })
+    }
+    
+    /// Receives an item without waiting, telling an empty queue apart from
+    /// a disconnected one.
+    /// 
+    /// This is `recv` with the result of `std::sync::mpsc::Receiver::try_recv`:
+    /// `Err(TryRecvError::Disconnected)` once the queue is empty and every
+    /// `Producer` handle has been dropped, as in `recv_disconnected`, and
+    /// `Err(TryRecvError::Empty)` otherwise.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        if let Some(item) = self.recv() {
+            return Ok(item);
+        }
+        if self.producers.load(Ordering::Acquire) == 0 {
+            // The last producer's sends are visible now; take anything
+            // that landed between the recv above and the count check
+            return self.recv().ok_or(TryRecvError::Disconnected);
+        }
+        Err(TryRecvError::Empty)
+    }
+    
+    /// Receives an item, waiting asynchronously until one is available.
+    /// 
+    /// The task is woken by the next send, the same way `recv_blocking` is.
+    /// Dropping the future before it completes hands its place in line to
+    /// the next waiting consumer, so no wakeup is lost.
+    #[cfg(feature = "std")]
+    pub async fn recv_async(&self) -> T {
+        let mut waiter = Registration::new(&self.not_empty);
+        poll_fn(|cx| waiter.poll_until(cx, || self.recv())).await
+    }
+    
+    // Waits before retrying a contended or in-flight slot, as the
+    // queue's wait strategy decides
+    #[inline]
+    fn retry_wait(&self, attempt: &mut u32) {
+        self.wait_strategy.wait(*attempt);
+        // Under loom, a retry also has to hand control to the model's
+        // scheduler, or the thread we wait for never runs
+        #[cfg(loom)]
+        loom::thread::yield_now();
+        *attempt = attempt.saturating_add(1);
+    }
+    
+    /// `recv`, with `take` moving the item (and anything stored alongside
+    /// it) out of the slot claimed at the given position before the slot is
+    /// released.
+    #[inline]
+    fn recv_with<R>(&self, take: impl FnOnce(&Slot<T>, usize) -> R) -> Option<R> {
+        let mut attempt = 0u32;
+        loop {
+            // Relaxed: see `send_tagging`, positions carry no data
+            let tail = self.consumer_pos.tail.load(Ordering::Relaxed);
+            let slot = &self.buffer[self.index(tail)];
+            
+            // Acquire: pairs with the producer's Release, making the item
+            // (and its abandoned flag and tag) visible before we read them
+            let seq = slot.sequence.load(Ordering::Acquire);
+            let expected_seq = next_seq(tail);
+            
+            match compare(seq, expected_seq) {
+                core::cmp::Ordering::Equal => {
+                    // Data is available, try to claim it. Relaxed: the CAS
+                    // only decides which consumer reads the slot
+                    match self.consumer_pos.tail.compare_exchange_weak(
+                        tail,
+                        next_seq(tail),
+                        Ordering::Relaxed,
+                        Ordering::Relaxed,
+                    ) {
+                        Ok(_) => {
+                            if slot.take_abandoned() {
+                                // Nothing to read, release the slot and move on
+                                slot.sequence.store(
+                                    word(free_seq(tail, self.capacity)),
+                                    Ordering::Release,
+                                );
+                                self.not_full.notify_one();
+                                self.notify_if_drained(next_seq(tail));
+                                continue;
+                            }
+                            
+                            // Successfully claimed the slot, read the data
+                            let item = take(slot, tail);
+                            self.count_received(1);
+                            
+                            // Release: orders our read before the next
+                            // lap's producer overwrites the slot
+                            slot.sequence.store(
+                                word(free_seq(tail, self.capacity)),
+                                Ordering::Release,
+                            );
+                            self.not_full.notify_one();
+                            self.notify_if_drained(next_seq(tail));
+                            return Some(item);
+                        }
+                        Err(_) => {
+                            // Another consumer claimed this slot, retry
+                            self.retry_wait(&mut attempt);
+                            continue;
+                        }
+                    }
+                }
+                core::cmp::Ordering::Less => {
+                    // No data available, queue is empty
+                    return None;
+                }
+                core::cmp::Ordering::Greater => {
+                    // Slot is ahead: another consumer already claimed `tail`,
+                    // so wait until we see tail past it (see `send_tagging`)
+                    while self.consumer_pos.tail.load(Ordering::Acquire) == tail {
+                        self.retry_wait(&mut attempt);
+                    }
+                    continue;
+                }
+            }
+        }
+    }
+    
+    /// Creates a queue whose consumer side is owned by a single
+    /// `ExclusiveConsumer`, returning it alongside the first `Producer`.
+    /// 
+    /// Producers can be cloned freely, so any number of threads can send.
+    /// The queue itself is never handed out, which guarantees that the
+    /// returned consumer is the only thing that can ever advance the tail.
+    pub fn with_exclusive_consumer(capacity: usize) -> (Producer<T>, ExclusiveConsumer<T>) {
+        let queue = Arc::new(Self::new(capacity));
+        let producer = Producer::new(Arc::clone(&queue));
+        queue.consumers.fetch_add(1, Ordering::Relaxed);
+        (producer, ExclusiveConsumer { queue })
+    }
+    
+    /// Creates a queue whose consumers are served in arrival order,
+    /// returning the first `Producer` and the first `FairConsumer`.
+    /// 
+    /// Clone the handles for more producers and consumers. As with
+    /// `with_exclusive_consumer`, the queue itself is never handed out, so
+    /// no plain consumer can jump the line. See `FairConsumer` for the
+    /// latency bound and its cost in throughput.
+    pub fn with_fair_consumers(capacity: usize) -> (Producer<T>, FairConsumer<T>) {
+        let queue = Arc::new(Self::new(capacity));
+        (Producer::new(Arc::clone(&queue)), FairConsumer::new(queue))
+    }
+    
+    /// Creates a queue with exactly one producer and one consumer, both
+    /// exclusive.
+    /// 
+    /// Neither side competes with anything, so `ExclusiveProducer::send`
+    /// advances the head with a plain store just as `ExclusiveConsumer::recv`
+    /// does the tail; the slot sequence numbers still carry the item across
+    /// threads. Exclusivity is enforced by the types: neither handle is
+    /// `Clone`, both operations take `&mut self`, and the queue itself is
+    /// never handed out, so safe code cannot add a second producer or
+    /// consumer.
+    pub fn spsc(capacity: usize) -> (ExclusiveProducer<T>, ExclusiveConsumer<T>) {
+        let queue = Arc::new(Self::new(capacity));
+        queue.producers.fetch_add(1, Ordering::Relaxed);
+        queue.consumers.fetch_add(1, Ordering::Relaxed);
+        (ExclusiveProducer { queue: Arc::clone(&queue) }, ExclusiveConsumer { queue })
+    }
+    
+    /// Single-producer send: the caller must be the only thread that
+    /// advances the head, which lets it skip the CAS loop in `send`.
+    fn send_exclusive(&self, item: T) -> Result<(), T> {
+        let head = self.producer_pos.head.load(Ordering::Relaxed);
+        let slot = &self.buffer[self.index(head)];
+        
+        if slot.sequence.load(Ordering::Acquire) != word(head) {
+            // The slot still holds last lap's item, queue is full
+            self.count_send_failure();
+            return Err(item);
+        }
+        
+        slot.set_producer(NO_PRODUCER);
+        unsafe {
+            slot.data.with_mut(|data| (*data).write(item));
+        }
+        
+        // No other producer can race for this slot, a plain store claims it
+        self.producer_pos.head.store(next_seq(head), Ordering::Release);
+        slot.sequence.store(word(next_seq(head)), Ordering::Release);
+        self.count_sent(1);
+        self.not_empty.notify_one();
+        Ok(())
+    }
+    
+    /// Single-consumer receive: the caller must be the only thread that
+    /// advances the tail, which lets it skip the CAS loop in `recv`.
+    fn recv_exclusive(&self) -> Option<T> {
+        loop {
+            let tail = self.consumer_pos.tail.load(Ordering::Relaxed);
+            let slot = &self.buffer[self.index(tail)];
+            
+            if slot.sequence.load(Ordering::Acquire) != word(next_seq(tail)) {
+                // No data available, queue is empty
+                return None;
+            }
+            
+            let item = if slot.take_abandoned() {
+                None
+            } else {
+                Some(unsafe { slot.take() })
+            };
+            
+            // No other consumer can race for this slot, a plain store claims it
+            self.release_exclusive(tail);
+            if item.is_some() {
+                self.count_received(1);
+                return item;
+            }
+        }
+    }
+    
+    // Single-consumer only: finds the next readable slot without claiming
+    // it, stepping over abandoned slots, and returns its position
+    fn front_exclusive(&self) -> Option<usize> {
+        loop {
+            let tail = self.consumer_pos.tail.load(Ordering::Relaxed);
+            let slot = &self.buffer[self.index(tail)];
+            
+            if slot.sequence.load(Ordering::Acquire) != word(next_seq(tail)) {
+                return None;
+            }
+            if !slot.take_abandoned() {
+                return Some(tail);
+            }
+            self.release_exclusive(tail);
+        }
+    }
+    
+    /// Receives an item together with the id of the `Producer` that sent it.
+    /// 
+    /// The id is the one reported by `Producer::id`; items sent directly
+    /// through `MpmcQueue::send` (or a wrapper built on it) are tagged None.
+    /// 
+    /// Tagging costs one `usize` per slot. Slots are cache-line sized, so
+    /// this is free while the item still fits next to the sequence number in
+    /// 64 bytes (items up to 48 bytes); beyond that every slot grows by a
+    /// whole cache line.
+    #[cfg(feature = "producer-tags")]
+    pub fn recv_tagged(&self) -> Option<(Option<usize>, T)> {
+        self.recv_with(|slot, _| unsafe {
+            let producer = slot.producer.with(|producer| *producer);
+            let item = slot.take();
+            ((producer != NO_PRODUCER).then_some(producer), item)
+        })
+    }
+    
+    /// Drains all currently available items into `f` and returns how many
+    /// came from each producer id (see `recv_tagged`).
+    /// 
+    /// Meant for diagnosing producer imbalance, e.g. a single producer
+    /// flooding the queue.
+    #[cfg(all(feature = "producer-tags", feature = "std"))]
+    pub fn drain_producer_counts<F: FnMut(T)>(&self, mut f: F) -> std::collections::HashMap<Option<usize>, usize> {
+        let mut counts = std::collections::HashMap::new();
+        while let Some((producer, item)) = self.recv_tagged() {
+            *counts.entry(producer).or_insert(0) += 1;
+            f(item);
+        }
+        counts
+    }
+    
+    /// Returns the capacity of the queue.
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the bytes of the ring backing the queue: `capacity()` slots
+    /// of `slot_size()` bytes each.
+    ///
+    /// The queue struct and any heap data the items own are not counted.
+    pub const fn memory_footprint(&self) -> usize {
+        self.capacity * Self::slot_size()
+    }
+
+    /// Returns the bytes each slot of the ring takes, including padding.
+    ///
+    /// Slots are cache-line aligned to avoid false sharing, so a slot takes
+    /// at least 64 bytes (128 on Apple silicon, 256 on s390x) whatever the
+    /// size of `T`: a queue of 1M `u64`s takes 64MB, not 8MB.
+    pub const fn slot_size() -> usize {
+        core::mem::size_of::<Slot<T>>()
+    }
+
+    /// Returns true if the queue is empty.
+    /// 
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn is_empty(&self) -> bool {
+        let head = self.producer_pos.head.load(Ordering::Acquire);
+        let tail = self.consumer_pos.tail.load(Ordering::Acquire);
+        head == tail
+    }
+    
+    /// Returns true if the queue is full.
+    /// 
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn is_full(&self) -> bool {
+        self.occupancy() >= self.capacity
+    }
+    
+    /// Returns the approximate number of items in the queue.
+    /// 
+    /// Note: This is a snapshot view and may change immediately after the call.
+    /// 
+    /// Never more than `capacity()`, so `len() <= capacity()` holds even
+    /// while the queue is busy. Head and tail are loaded one after the
+    /// other, not together, so under contention the raw difference can
+    /// briefly overshoot when consumers and then producers both move on in
+    /// between; it is clamped. Slots claimed by producers but not yet
+    /// written count as items; see `available` for only the ready ones.
+    pub fn len(&self) -> usize {
+        self.occupancy()
+    }
+    
+    /// Returns the number of items that are ready to be received.
+    /// 
+    /// Unlike `len`, this leaves out slots that producers have claimed but
+    /// not yet written: it counts published slots from the tail up to the
+    /// first unpublished one, i.e. what consumers could take right now.
+    /// Slots abandoned by a panicking `send_generated` closure are counted.
+    /// 
+    /// Note: This scans up to `len()` slots and is a snapshot view that may
+    /// change immediately after the call.
+    pub fn available(&self) -> usize {
+        let tail = self.consumer_pos.tail.load(Ordering::Acquire);
+        self.count_from(tail, next_seq)
+    }
+    
+    /// Returns the number of slots that are free for producers to claim.
+    /// 
+    /// Unlike `capacity() - len()`, this leaves out slots that consumers have
+    /// claimed but not yet released: it counts free slots from the head up
+    /// to the first one still in use.
+    /// 
+    /// Note: This scans up to `capacity()` slots and is a snapshot view that
+    /// may change immediately after the call.
+    pub fn writable(&self) -> usize {
+        let head = self.producer_pos.head.load(Ordering::Acquire);
+        self.count_from(head, |pos| pos)
+    }
+    
+    /// Returns the producers' position: how many slots have been claimed
+    /// for sending since the queue was created, wrapping at `usize::MAX`.
+    /// 
+    /// Sampled periodically, the difference between two readings (taken
+    /// with `mpmc_std::seq::depth`, which handles the wrap) is the number
+    /// of sends in between, for throughput monitoring without the
+    /// `metrics` feature. Claimed slots count before they are written.
+    /// 
+    /// Note: This and `consumer_position` are separate loads, so reading
+    /// both gives positions from slightly different instants; their
+    /// difference can briefly exceed `capacity()`.
+    pub fn producer_position(&self) -> usize {
+        self.producer_pos.head.load(Ordering::Acquire)
+    }
+    
+    /// Returns the consumers' position: how many slots have been claimed
+    /// for receiving since the queue was created, wrapping at `usize::MAX`.
+    /// 
+    /// See `producer_position`.
+    pub fn consumer_position(&self) -> usize {
+        self.consumer_pos.tail.load(Ordering::Acquire)
+    }
+    
+    // Counts consecutive slots from `start` whose sequence is `expected(pos)`
+    fn count_from(&self, start: usize, expected: impl Fn(usize) -> usize) -> usize {
+        (0..self.capacity)
+            .take_while(|&i| {
+                let pos = advance(start, i);
+                self.buffer[self.index(pos)].sequence.load(Ordering::Acquire) == word(expected(pos))
+            })
+            .count()
+    }
+    
+    /// Returns the number of items sent through the queue since it was
+    /// created, by any send method.
+    /// 
+    /// The counters are plain relaxed increments, kept only with the
+    /// `metrics` feature. Read together they are not a consistent snapshot,
+    /// e.g. `total_sent() - total_received()` can briefly differ from `len()`.
+    #[cfg(feature = "metrics")]
+    pub fn total_sent(&self) -> u64 {
+        self.metrics.sent.load(Ordering::Relaxed)
+    }
+    
+    /// Returns the number of items received from the queue since it was
+    /// created, by any receive method. Abandoned slots are not counted.
+    #[cfg(feature = "metrics")]
+    pub fn total_received(&self) -> u64 {
+        self.metrics.received.load(Ordering::Relaxed)
+    }
+    
+    /// Returns the number of send attempts rejected because the queue was
+    /// full.
+    /// 
+    /// Every rejection counts, including those a blocking or async send
+    /// retries internally while it waits for room, so this measures how
+    /// often producers hit a full queue rather than how many items were lost.
+    #[cfg(feature = "metrics")]
+    pub fn send_failures(&self) -> u64 {
+        self.metrics.send_failures.load(Ordering::Relaxed)
+    }
+    
+    /// Returns the largest `len()` observed right after a successful send
+    /// since the queue was created or `reset_high_water_mark` was called.
+    /// 
+    /// A mark that stays well below `capacity()` means the queue is larger
+    /// than it needs to be; one at `capacity()` means producers have hit a
+    /// full queue. Like `len()` it counts claimed slots, so it can include
+    /// items that were still being written.
+    #[cfg(feature = "metrics")]
+    pub fn high_water_mark(&self) -> usize {
+        self.metrics.high_water_mark.load(Ordering::Relaxed)
+    }
+    
+    /// Restarts high-water-mark tracking from the current `len()`, e.g. at
+    /// the start of each monitoring interval.
+    #[cfg(feature = "metrics")]
+    pub fn reset_high_water_mark(&self) {
+        self.metrics.high_water_mark.store(self.len(), Ordering::Relaxed);
+    }
+    
+    /// Drains all currently available items, folding them into an accumulator.
+    ///
+    /// This is `Iterator::fold` over the queue's contents without collecting
+    /// them first, e.g. to sum or count a backlog in one pass.
+    ///
+    /// Note: This is a best-effort snapshot. Draining stops at the first empty
+    /// observation, so items sent concurrently may or may not be included.
+    pub fn drain_fold<A, F: FnMut(A, T) -> A>(&self, init: A, mut f: F) -> A {
+        let mut acc = init;
+        while let Some(item) = self.recv() {
+            acc = f(acc, item);
+        }
+        acc
+    }
+
+    /// Returns the number of live `Producer` handles attached to this queue.
+    pub fn producer_count(&self) -> usize {
+        self.producers.load(Ordering::Acquire)
+    }
+    
+    /// Returns the number of live `Consumer` (and `ExclusiveConsumer`)
+    /// handles attached to this queue.
+    pub fn consumer_count(&self) -> usize {
+        self.consumers.load(Ordering::Acquire)
+    }
+    
+    /// Returns true once the queue is empty and every `Producer` has been dropped.
+    #[cfg(feature = "std")]
+    fn is_complete(&self) -> bool {
+        // Acquire on the count makes the last producer's sends visible
+        // before we check for emptiness
+        self.producers.load(Ordering::Acquire) == 0 && self.is_empty()
+    }
+    
+    /// Waits until the queue is empty and every `Producer` handle has been dropped.
+    /// 
+    /// This is the end-of-stream signal: once it returns true, no item will
+    /// ever be sent through a `Producer` again and everything already sent has
+    /// been received. Returns false if the timeout elapses first.
+    /// 
+    /// The calling thread parks until the receive that empties the queue or
+    /// the drop of the last `Producer` wakes it, whichever completes the
+    /// condition, rather than polling.
+    /// 
+    /// The queue has no `close()`: dropping the last `Producer` is the
+    /// close. Once that happens, `await_completion` returns true as soon as
+    /// the items still queued have been received, and `recv_disconnected`
+    /// reports the disconnect. Only `Producer` handles are counted. Items
+    /// sent directly through the queue (e.g. via `Arc<MpmcQueue<T>>::send`)
+    /// are not tracked, a queue that never had a `Producer` completes as
+    /// soon as it is empty, and creating a new `Producer` reopens it.
+    #[cfg(feature = "std")]
+    pub fn await_completion(&self, timeout: Duration) -> bool {
+        // A timeout too large to represent means no deadline
+        let deadline = Instant::now().checked_add(timeout);
+        self.drained
+            .wait_until_deadline(deadline, || self.is_complete().then_some(()))
+            .is_some()
+    }
+    
+}
+
+// Separate impl block without Send bound for Drop implementation
+impl<T> MpmcQueue<T> {
+    /// Consumes the queue and returns its backing buffer together with the
+    /// producer (`head`) and consumer (`tail`) positions, as `(buffer, head, tail)`.
+    /// 
+    /// This is an escape hatch for profiling teardown, custom drain logic or
+    /// reusing the allocation. The queue's `Drop` drain does not run: the
+    /// caller takes ownership of every item still in the buffer. For each
+    /// position `pos` in `tail..head` (wrapping), the slot at
+    /// `pos % buffer.len()` holds an initialized item when its `sequence()`
+    /// equals `seq::word(pos + 1)` and it is not `is_abandoned()`; this is
+    /// the queue's index rule for power-of-2 and `new_exact` capacities
+    /// alike. The caller must read or drop such items through `data_ptr()`,
+    /// or hand the parts back to `from_raw_parts`. Dropping the returned
+    /// buffer on its own leaks those items.
+    pub fn into_raw_parts(mut self) -> (Box<[Slot<T>]>, usize, usize) {
+        // Relaxed: owning the queue already orders every earlier access
+        let head = self.producer_pos.head.load(Ordering::Relaxed);
+        let tail = self.consumer_pos.tail.load(Ordering::Relaxed);
+        let buffer = core::mem::take(&mut self.buffer);
+        // Leave an empty queue behind so `Drop` has nothing to drain
+        self.consumer_pos.tail.store(head, Ordering::Relaxed);
+        (buffer, head, tail)
+    }
+    
+    /// Reassembles a queue from the parts returned by `into_raw_parts`.
+    /// 
+    /// The queue starts with no registered `Producer` handles.
+    /// 
+    /// # Safety
+    /// 
+    /// `buffer`, `head` and `tail` must come from `into_raw_parts` (possibly
+    /// after the caller consumed some items and updated sequence numbers
+    /// consistently), so that every slot's sequence number matches the
+    /// protocol for the given positions. The buffer length must be a power of
+    /// 2, or, on 64-bit targets, any length of at least 2 (see `new_exact`).
+    pub unsafe fn from_raw_parts(buffer: Box<[Slot<T>]>, head: usize, tail: usize) -> Self {
+        let capacity = buffer.len();
+        assert!(
+            capacity.is_power_of_two() || (cfg!(target_pointer_width = "64") && capacity >= 2),
+            "Buffer length must be a power of 2"
+        );
+        
+        Self {
+            buffer,
+            capacity,
+            mask: mask_for(capacity),
+            producer_pos: ProducerPos {
+                head: AtomicUsize::new(head),
+            },
+            consumer_pos: ConsumerPos {
+                tail: AtomicUsize::new(tail),
+            },
+            wait_strategy: Box::new(Backoff::default()),
+            on_full: OnFull::Error,
+            producers: AtomicUsize::new(0),
+            consumers: AtomicUsize::new(0),
+            not_empty: WaitList::new(),
+            not_full: WaitList::new(),
+            drained: WaitList::new(),
+            #[cfg(feature = "producer-tags")]
+            next_producer_id: AtomicUsize::new(0),
+            #[cfg(feature = "metrics")]
+            metrics: Metrics::default(),
+        }
+    }
+    
+    // Buffer index of the slot for `pos`
+    #[inline]
+    fn index(&self, pos: usize) -> usize {
+        if self.mask != NO_MASK {
+            pos & self.mask
+        } else {
+            pos % self.capacity
+        }
+    }
+    
+    // Single-consumer only: hands the slot at `tail` back to producers
+    fn release_exclusive(&self, tail: usize) {
+        self.consumer_pos.tail.store(next_seq(tail), Ordering::Release);
+        self.buffer[self.index(tail)]
+            .sequence
+            .store(word(free_seq(tail, self.capacity)), Ordering::Release);
+        self.not_full.notify_one();
+        self.notify_if_drained(next_seq(tail));
+    }
+    
+    // Wakes `await_completion` waiters if a receive that moved the tail up
+    // to `tail` emptied the queue. The head cannot be behind the items we
+    // just received, so it only equals `tail` if none are left. Callers
+    // have just notified `not_full`, whose fence covers this list too
+    #[inline]
+    fn notify_if_drained(&self, tail: usize) {
+        #[cfg(feature = "std")]
+        if self.producer_pos.head.load(Ordering::Relaxed) == tail {
+            self.drained.notify_all_fenced();
+        }
+        #[cfg(not(feature = "std"))]
+        let _ = tail;
+    }
+    
+    // For an owned queue, returns the slot at `pos` if it holds an item:
+    // every claimed position is walked rather than stopping at the first
+    // unpublished slot, see `Drop`. Relaxed loads suffice, as nothing else
+    // can touch the ring
+    fn owned_item(&self, pos: usize) -> Option<&Slot<T>> {
+        let slot = &self.buffer[self.index(pos)];
+        (slot.sequence.load(Ordering::Relaxed) == word(next_seq(pos)) && !slot.take_abandoned()).then_some(slot)
+    }
+    
+    // Metrics and tracing bookkeeping; no-ops without the `metrics` and
+    // `tracing` features
+    #[inline]
+    fn count_sent(&self, _items: usize) {
+        #[cfg(feature = "metrics")]
+        {
+            self.metrics.sent.fetch_add(_items as u64, Ordering::Relaxed);
+            self.metrics.high_water_mark.fetch_max(self.occupancy(), Ordering::Relaxed);
+        }
+        #[cfg(feature = "tracing")]
+        tracing::trace!(items = _items, len = self.occupancy(), "sent");
+    }
+    
+    // `len()` without the Send bound. Tail is read first, so a consumer
+    // racing ahead cannot make it wrap; it can overshoot, hence the clamp
+    fn occupancy(&self) -> usize {
+        let tail = self.consumer_pos.tail.load(Ordering::Acquire);
+        let head = self.producer_pos.head.load(Ordering::Acquire);
+        depth(head, tail).min(self.capacity)
+    }
+    
+    #[inline]
+    fn count_received(&self, _items: usize) {
+        #[cfg(feature = "metrics")]
+        self.metrics.received.fetch_add(_items as u64, Ordering::Relaxed);
+        #[cfg(feature = "tracing")]
+        tracing::trace!(items = _items, len = self.occupancy(), "received");
+    }
+    
+    #[inline]
+    fn count_send_failure(&self) {
+        #[cfg(feature = "metrics")]
+        self.metrics.send_failures.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "tracing")]
+        tracing::warn!(capacity = self.capacity, "send rejected, queue is full");
+    }
+}
+
+// Snapshot state only, so T need not be Debug
+impl<T: Send> fmt::Debug for MpmcQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MpmcQueue")
+            .field("capacity", &self.capacity())
+            .field("len", &self.len())
+            .field("is_empty", &self.is_empty())
+            .field("is_full", &self.is_full())
+            .finish()
+    }
+}
+
+/// Best-effort bulk send: items that do not fit once the queue is full are
+/// dropped. Use `MpmcQueue::try_extend` to get them back.
+impl<T: Send> Extend<T> for MpmcQueue<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, items: I) {
+        drop(self.try_extend(items));
+    }
+}
+
+/// Builds a queue holding every item, with the capacity rounded up from the
+/// item count as in `MpmcQueue::new`.
+impl<T: Send> FromIterator<T> for MpmcQueue<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(items: I) -> Self {
+        let items: Vec<T> = items.into_iter().collect();
+        // `new` rounds up to its minimum; only an empty iterator needs help,
+        // since a capacity of 0 panics
+        let queue = Self::new(items.len().max(1));
+        if queue.send_iter(items).is_err() {
+            unreachable!("the queue was sized to fit every item");
+        }
+        queue
+    }
+}
+
+impl<T> Drop for MpmcQueue<T> {
+    fn drop(&mut self) {
+        // With `&mut self` no other thread can touch the ring, so walk every
+        // claimed position rather than stopping at the first unpublished
+        // slot: a producer that claimed a slot and never wrote it (e.g. it
+        // panicked) must not hide the items published after it. Relaxed
+        // loads suffice for the same reason
+        let head = self.producer_pos.head.load(Ordering::Relaxed);
+        let tail = self.consumer_pos.tail.load(Ordering::Relaxed);
+        let mut items = Teardown {
+            queue: self,
+            next: tail,
+            end: head,
+        };
+        items.drop_rest();
+    }
+}
+
+// Drops the items left in `next..end` when the queue is dropped. If an
+// item's destructor panics, dropping the guard during unwinding carries on
+// with the items after it, so one bad destructor does not leak the rest. A
+// second panic while unwinding aborts, as when dropping a `Vec`
+struct Teardown<'a, T> {
+    queue: &'a MpmcQueue<T>,
+    next: usize,
+    end: usize,
+}
+
+impl<T> Teardown<'_, T> {
+    fn drop_rest(&mut self) {
+        while self.next != self.end {
+            let pos = self.next;
+            // Step past the item first, so it is not dropped again
+            self.next = next_seq(pos);
+            if let Some(slot) = self.queue.owned_item(pos) {
+                unsafe {
+                    slot.discard();
+                }
+            }
+        }
+    }
+}
+
+impl<T> Drop for Teardown<'_, T> {
+    fn drop(&mut self) {
+        self.drop_rest();
+    }
+}
+
+unsafe impl<T: Send> Send for MpmcQueue<T> {}
+unsafe impl<T: Send> Sync for MpmcQueue<T> {}
+
+// Publishes the claimed slots in `next..end` that were never filled as
+// abandoned, so a panicking fill leaves the queue usable, then wakes
+// consumers for the run
+struct Abandon<'a, T> {
+    queue: &'a MpmcQueue<T>,
+    next: usize,
+    end: usize,
+    multiple: bool,
+}
+
+impl<T> Drop for Abandon<'_, T> {
+    fn drop(&mut self) {
+        while self.next != self.end {
+            let slot = &self.queue.buffer[self.queue.index(self.next)];
+            unsafe {
+                slot.abandoned.with_mut(|abandoned| *abandoned = true);
+            }
+            slot.sequence.store(word(next_seq(self.next)), Ordering::Release);
+            self.next = next_seq(self.next);
+        }
+        if self.multiple {
+            self.queue.not_empty.notify_all();
+        } else {
+            self.queue.not_empty.notify_one();
+        }
+    }
+}
+
+/// Backs off from spinning to short sleeps while polling for a condition.
+#[cfg(feature = "std")]
+fn wait_backoff(attempt: &mut u32) {
+    Sleeping.wait(*attempt);
+    *attempt = attempt.saturating_add(1);
+}
+
+/// A producer handle for the MPMC queue.
+/// 
+/// Multiple producers can send items concurrently.
+pub struct Producer<T> {
+    queue: Arc<MpmcQueue<T>>,
+    #[cfg(feature = "producer-tags")]
+    id: usize,
+}
+
+impl<T: Send> Producer<T> {
+    pub fn new(queue: Arc<MpmcQueue<T>>) -> Self {
+        queue.producers.fetch_add(1, Ordering::Relaxed);
+        Self {
+            #[cfg(feature = "producer-tags")]
+            id: queue.next_producer_id.fetch_add(1, Ordering::Relaxed),
+            queue,
+        }
+    }
+    
+    /// Sends an item to the queue.
+    /// 
+    /// This is now a synchronous, wait-free operation. A queue built with
+    /// `MpmcQueue::with_policy` applies its `OnFull` policy instead of
+    /// failing.
+    pub fn send(&self, item: T) -> Result<(), T> {
+        self.queue.send_with_policy(item, |item| self.send_now(item))
+    }
+    
+    // `send` without the `OnFull` policy
+    fn send_now(&self, item: T) -> Result<(), T> {
+        self.send_pos(item).map(|_| ())
+    }
+    
+    /// Sends an item and returns a token that reports when it has been
+    /// received.
+    /// 
+    /// See `MpmcQueue::send_tracked`.
+    pub fn send_tracked(&self, item: T) -> Result<AckToken<'_, T>, T> {
+        let pos = self.send_pos(item)?;
+        Ok(AckToken { queue: &self.queue, pos })
+    }
+    
+    // Sends tagged with this producer's id, returning the item's position
+    fn send_pos(&self, item: T) -> Result<usize, T> {
+        #[cfg(feature = "producer-tags")]
+        let producer = self.id;
+        #[cfg(not(feature = "producer-tags"))]
+        let producer = NO_PRODUCER;
+        self.queue.send_tagging(item, |slot| slot.set_producer(producer))
+    }
+    
+    /// Sends an item, failing fast once nobody is left to receive it.
+    ///
+    /// Returns `Err(SendError::Disconnected(item))` when every `Consumer`
+    /// handle has been dropped, instead of filling a queue nobody will
+    /// drain, and `Err(SendError::Full(item))` when the queue is full.
+    ///
+    /// Only consumer handles are counted: a queue that is only ever drained
+    /// through `MpmcQueue::recv` directly always looks disconnected here.
+    pub fn send_checked(&self, item: T) -> Result<(), SendError<T>> {
+        if self.queue.consumers.load(Ordering::Acquire) == 0 {
+            return Err(SendError::Disconnected(item));
+        }
+        self.send_now(item).map_err(SendError::Full)
+    }
+    
+    /// Sends an item without waiting, under the name std's and crossbeam's
+    /// channels use.
+    /// 
+    /// Same as `send_checked`, so code written against
+    /// `std::sync::mpsc::SyncSender::try_send` keeps matching on
+    /// `TrySendError::Full` and `TrySendError::Disconnected`.
+    pub fn try_send(&self, item: T) -> Result<(), TrySendError<T>> {
+        self.send_checked(item)
+    }
+    
+    /// Sends an item, parking until there is room for it.
+    ///
+    /// See `MpmcQueue::send_blocking`.
+    #[cfg(feature = "std")]
+    pub fn send_blocking(&self, item: T) {
+        let sent = self.queue.send_parked(item, None, |item| self.send_now(item));
+        debug_assert!(sent.is_ok());
+    }
+    
+    /// Sends an item, parking for at most `timeout` until there is room.
+    ///
+    /// See `MpmcQueue::send_timeout`.
+    #[cfg(feature = "std")]
+    pub fn send_timeout(&self, item: T, timeout: Duration) -> Result<(), SendTimeoutError<T>> {
+        self.send_deadline_opt(item, Instant::now().checked_add(timeout))
+    }
+    
+    /// Sends an item, parking until there is room or `deadline` passes.
+    ///
+    /// See `MpmcQueue::send_deadline`.
+    #[cfg(feature = "std")]
+    pub fn send_deadline(&self, item: T, deadline: Instant) -> Result<(), SendTimeoutError<T>> {
+        self.send_deadline_opt(item, Some(deadline))
+    }
+    
+    #[cfg(feature = "std")]
+    fn send_deadline_opt(&self, item: T, deadline: Option<Instant>) -> Result<(), SendTimeoutError<T>> {
+        self.queue
+            .send_parked(item, deadline, |item| self.send_now(item))
+            .map_err(SendTimeoutError::Timeout)
+    }
+    
+    /// Returns this handle's producer id, unique per queue.
+    /// 
+    /// Ids are assigned in creation order starting at 0; a clone gets a new id.
+    #[cfg(feature = "producer-tags")]
+    pub fn id(&self) -> usize {
+        self.id
+    }
+    
+    /// Sends an item, waiting asynchronously until there is room for it.
+    ///
+    /// See `MpmcQueue::send_async`.
+    #[cfg(feature = "std")]
+    pub async fn send_async(&self, item: T) {
+        self.queue.send_polled(item, |item| self.send_now(item)).await
+    }
+    
+    /// Returns true if the queue is full.
+    pub fn is_full(&self) -> bool {
+        self.queue.is_full()
+    }
+    
+    /// Returns the number of slots free to claim.
+    ///
+    /// See `MpmcQueue::writable`.
+    pub fn writable(&self) -> usize {
+        self.queue.writable()
+    }
+    
+    /// Returns the number of items sent through the queue so far.
+    /// 
+    /// See `MpmcQueue::total_sent`.
+    #[cfg(feature = "metrics")]
+    pub fn total_sent(&self) -> u64 {
+        self.queue.total_sent()
+    }
+    
+    /// Returns the number of sends rejected because the queue was full.
+    /// 
+    /// See `MpmcQueue::send_failures`.
+    #[cfg(feature = "metrics")]
+    pub fn send_failures(&self) -> u64 {
+        self.queue.send_failures()
+    }
+    
+    /// Returns the largest queue length observed after a send.
+    /// 
+    /// See `MpmcQueue::high_water_mark`.
+    #[cfg(feature = "metrics")]
+    pub fn high_water_mark(&self) -> usize {
+        self.queue.high_water_mark()
+    }
+    
+    /// Returns the capacity of the queue.
+    pub fn capacity(&self) -> usize {
+        self.queue.capacity()
+    }
+    
+    /// Creates a `WeakProducer` for this queue, which does not keep the
+    /// queue alive.
+    pub fn downgrade(&self) -> WeakProducer<T> {
+        WeakProducer {
+            queue: Arc::downgrade(&self.queue),
+        }
+    }
+}
+
+impl<T: Send> Clone for Producer<T> {
+    fn clone(&self) -> Self {
+        Self::new(Arc::clone(&self.queue))
+    }
+}
+
+impl<T: Send> fmt::Debug for Producer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Producer");
+        #[cfg(feature = "producer-tags")]
+        debug.field("id", &self.id);
+        debug
+            .field("queue", &self.queue)
+            .field("handles", &Arc::strong_count(&self.queue))
+            .finish()
+    }
+}
+
+impl<T> Drop for Producer<T> {
+    fn drop(&mut self) {
+        // Release pairs with the Acquire in `producer_count`/`await_completion`
+        if self.queue.producers.fetch_sub(1, Ordering::Release) == 1 {
+            // Consumers parked in `recv_disconnected` must see the disconnect,
+            // and `await_completion` waiters the close
+            self.queue.not_empty.notify_all();
+            self.queue.drained.notify_all();
+        }
+    }
+}
+
+/// A producer handle that does not keep the queue alive, created by
+/// `Producer::downgrade`.
+/// 
+/// For registries and caches that hold on to handles without owning the
+/// queue: once every `Producer`, `Consumer` and other `Arc` of the queue is
+/// dropped, the queue and its items are dropped too, and `upgrade` returns
+/// None from then on. A weak handle is not a producer: it does not count
+/// in `producer_count`, so holding one does not stop consumers from seeing
+/// the disconnect.
+pub struct WeakProducer<T> {
+    queue: Weak<MpmcQueue<T>>,
+}
+
+impl<T: Send> WeakProducer<T> {
+    /// Returns a new `Producer` for the queue, or None if it was dropped.
+    /// 
+    /// The check and the new strong reference are one atomic step, so a
+    /// returned producer always has a live queue.
+    pub fn upgrade(&self) -> Option<Producer<T>> {
+        self.queue.upgrade().map(Producer::new)
+    }
+}
+
+impl<T> Clone for WeakProducer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            queue: Weak::clone(&self.queue),
+        }
+    }
+}
+
+impl<T> fmt::Debug for WeakProducer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeakProducer")
+            .field("handles", &self.queue.strong_count())
+            .finish()
+    }
+}
+
+/// A consumer handle for the MPMC queue.
+/// 
+/// Multiple consumers can receive items concurrently.
+pub struct Consumer<T> {
+    queue: Arc<MpmcQueue<T>>,
+}
+
+impl<T: Send> Consumer<T> {
+    pub fn new(queue: Arc<MpmcQueue<T>>) -> Self {
+        queue.consumers.fetch_add(1, Ordering::Relaxed);
+        Self { queue }
+    }
+    
+    /// Receives an item from the queue.
+    /// 
+    /// This is now a synchronous, wait-free operation.
+    pub fn recv(&self) -> Option<T> {
         self.queue.recv()
     }
     
-    /// Async version of recv for compatibility with existing code.
-    pub async fn recv_async(&self) -> Option<T> {
-        // Since the new implementation is wait-free, we can call it directly
-        // without spawn_blocking
-        self.recv()
+    /// Receives an item together with the queue position it was sent at.
+    /// 
+    /// See `MpmcQueue::recv_seq`.
+    pub fn recv_seq(&self) -> Option<(T, usize)> {
+        self.queue.recv_seq()
+    }
+    
+    /// Receives an item without waiting, telling an empty queue apart from
+    /// a disconnected one.
+    /// 
+    /// See `MpmcQueue::try_recv`.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        self.queue.try_recv()
+    }
+    
+    /// Returns an iterator over the items already in the queue.
+    ///
+    /// Like `std::sync::mpsc::Receiver::try_iter`, it never blocks and ends
+    /// at the first `recv` that finds the queue empty, which makes
+    /// `for msg in consumer.try_iter() { ... }` a natural step in an event loop.
+    pub fn try_iter(&self) -> TryIter<'_, T> {
+        TryIter { consumer: self }
+    }
+    
+    /// Receives an item, parking until one is available.
+    ///
+    /// See `MpmcQueue::recv_blocking`.
+    #[cfg(feature = "std")]
+    pub fn recv_blocking(&self) -> T {
+        self.queue.recv_blocking()
+    }
+    
+    /// Receives an item, parking for at most `timeout`.
+    ///
+    /// See `MpmcQueue::recv_timeout`.
+    #[cfg(feature = "std")]
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvTimeoutError> {
+        self.queue.recv_timeout(timeout)
+    }
+    
+    /// Receives an item, parking until `deadline` at the latest.
+    ///
+    /// See `MpmcQueue::recv_deadline`.
+    #[cfg(feature = "std")]
+    pub fn recv_deadline(&self, deadline: Instant) -> Result<T, RecvTimeoutError> {
+        self.queue.recv_deadline(deadline)
+    }
+    
+    /// Receives into `out` until it is full or `timeout` has elapsed.
+    ///
+    /// See `MpmcQueue::recv_batch_timeout`.
+    #[cfg(feature = "std")]
+    pub fn recv_batch_timeout(&self, out: &mut [T], timeout: Duration) -> usize {
+        self.queue.recv_batch_timeout(out, timeout)
+    }
+    
+    /// Appends up to `max` ready items to `out`, returning how many were
+    /// appended.
+    ///
+    /// See `MpmcQueue::recv_many`.
+    pub fn recv_many(&self, out: &mut Vec<T>, max: usize) -> usize {
+        self.queue.recv_many(out, max)
+    }
+    
+    /// Receives an item, parking until one is available or every
+    /// `Producer` has been dropped.
+    ///
+    /// See `MpmcQueue::recv_disconnected`.
+    #[cfg(feature = "std")]
+    pub fn recv_disconnected(&self) -> Result<T, RecvError> {
+        self.queue.recv_disconnected()
+    }
+    
+    /// Receives an item, waiting asynchronously until one is available.
+    ///
+    /// See `MpmcQueue::recv_async`.
+    #[cfg(feature = "std")]
+    pub async fn recv_async(&self) -> T {
+        self.queue.recv_async().await
     }
     
     /// Returns true if the queue is empty.
@@ -367,16 +2387,656 @@ impl<T: Send> Consumer<T> {
     pub fn len(&self) -> usize {
         self.queue.len()
     }
+    
+    /// Returns the number of items ready to be received.
+    ///
+    /// See `MpmcQueue::available`.
+    pub fn available(&self) -> usize {
+        self.queue.available()
+    }
+    
+    /// Returns the number of items received from the queue so far.
+    /// 
+    /// See `MpmcQueue::total_received`.
+    #[cfg(feature = "metrics")]
+    pub fn total_received(&self) -> u64 {
+        self.queue.total_received()
+    }
+    
+    /// Drains all currently available items, folding them into an accumulator.
+    ///
+    /// See `MpmcQueue::drain_fold`.
+    pub fn drain_fold<A, F: FnMut(A, T) -> A>(&self, init: A, f: F) -> A {
+        self.queue.drain_fold(init, f)
+    }
+    
+    /// Drops every item that is ready, returning how many were removed.
+    ///
+    /// See `MpmcQueue::clear`.
+    pub fn clear(&self) -> usize {
+        self.queue.clear()
+    }
+
+    /// Waits until the queue is empty and every `Producer` has been dropped.
+    ///
+    /// See `MpmcQueue::await_completion`.
+    #[cfg(feature = "std")]
+    pub fn await_completion(&self, timeout: Duration) -> bool {
+        self.queue.await_completion(timeout)
+    }
+    
+    /// Receives an item together with its producer id.
+    ///
+    /// See `MpmcQueue::recv_tagged`.
+    #[cfg(feature = "producer-tags")]
+    pub fn recv_tagged(&self) -> Option<(Option<usize>, T)> {
+        self.queue.recv_tagged()
+    }
+    
+    /// Drains available items and counts them per producer id.
+    ///
+    /// See `MpmcQueue::drain_producer_counts`.
+    #[cfg(all(feature = "producer-tags", feature = "std"))]
+    pub fn drain_producer_counts<F: FnMut(T)>(&self, f: F) -> std::collections::HashMap<Option<usize>, usize> {
+        self.queue.drain_producer_counts(f)
+    }
+    
+    /// Creates a `WeakConsumer` for this queue, which does not keep the
+    /// queue alive.
+    pub fn downgrade(&self) -> WeakConsumer<T> {
+        WeakConsumer {
+            queue: Arc::downgrade(&self.queue),
+        }
+    }
 }
 
 impl<T: Send> Clone for Consumer<T> {
+    fn clone(&self) -> Self {
+        Self::new(Arc::clone(&self.queue))
+    }
+}
+
+impl<T: Send> fmt::Debug for Consumer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Consumer")
+            .field("queue", &self.queue)
+            .field("handles", &Arc::strong_count(&self.queue))
+            .finish()
+    }
+}
+
+impl<T> Drop for Consumer<T> {
+    fn drop(&mut self) {
+        // Release pairs with the Acquire in `consumer_count`/`send_checked`
+        self.queue.consumers.fetch_sub(1, Ordering::Release);
+    }
+}
+
+/// A consumer handle that does not keep the queue alive, created by
+/// `Consumer::downgrade`.
+/// 
+/// See `WeakProducer`. A weak consumer does not count in
+/// `consumer_count` either.
+pub struct WeakConsumer<T> {
+    queue: Weak<MpmcQueue<T>>,
+}
+
+impl<T: Send> WeakConsumer<T> {
+    /// Returns a new `Consumer` for the queue, or None if it was dropped.
+    /// 
+    /// See `WeakProducer::upgrade`.
+    pub fn upgrade(&self) -> Option<Consumer<T>> {
+        self.queue.upgrade().map(Consumer::new)
+    }
+}
+
+impl<T> Clone for WeakConsumer<T> {
     fn clone(&self) -> Self {
         Self {
-            queue: Arc::clone(&self.queue),
+            queue: Weak::clone(&self.queue),
+        }
+    }
+}
+
+impl<T> fmt::Debug for WeakConsumer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WeakConsumer")
+            .field("handles", &self.queue.strong_count())
+            .finish()
+    }
+}
+
+/// The sole producer of a queue created with `MpmcQueue::spsc`.
+/// 
+/// Counterpart of `ExclusiveConsumer`: since nothing else can send to the
+/// queue, `send` advances the head with a plain store instead of a
+/// `compare_exchange_weak` loop.
+pub struct ExclusiveProducer<T> {
+    queue: Arc<MpmcQueue<T>>,
+}
+
+impl<T: Send> ExclusiveProducer<T> {
+    /// Sends an item to the queue.
+    /// 
+    /// Returns the item back if the queue is full.
+    pub fn send(&mut self, item: T) -> Result<(), T> {
+        self.queue.send_exclusive(item)
+    }
+    
+    /// Returns true if the queue is full.
+    pub fn is_full(&self) -> bool {
+        self.queue.is_full()
+    }
+    
+    /// Returns the approximate number of items in the queue.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+    
+    /// Returns true if the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+    
+    /// Returns the capacity of the queue.
+    pub fn capacity(&self) -> usize {
+        self.queue.capacity()
+    }
+}
+
+impl<T: Send> fmt::Debug for ExclusiveProducer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExclusiveProducer")
+            .field("queue", &self.queue)
+            .field("handles", &Arc::strong_count(&self.queue))
+            .finish()
+    }
+}
+
+impl<T> Drop for ExclusiveProducer<T> {
+    fn drop(&mut self) {
+        self.queue.producers.fetch_sub(1, Ordering::Release);
+    }
+}
+
+/// The sole consumer of a queue created with `MpmcQueue::with_exclusive_consumer`
+/// or `MpmcQueue::spsc`.
+/// 
+/// Built for the multi-producer, single-consumer topology: since nothing else
+/// can receive from the queue, `recv` advances the tail with a plain store
+/// instead of a `compare_exchange_weak` loop. Exclusivity is enforced by the
+/// type system: exactly one is created per queue, it is not `Clone`, and
+/// `recv` takes `&mut self`, so it cannot be called from two threads at once.
+pub struct ExclusiveConsumer<T> {
+    queue: Arc<MpmcQueue<T>>,
+}
+
+impl<T: Send> ExclusiveConsumer<T> {
+    /// Receives an item from the queue.
+    /// 
+    /// Returns None if the queue is empty.
+    pub fn recv(&mut self) -> Option<T> {
+        self.queue.recv_exclusive()
+    }
+    
+    /// Borrows the next item in place instead of moving it out of the queue.
+    /// 
+    /// The guard derefs to the item inside its slot; dropping the guard drops
+    /// the item and hands the slot back to producers. For large items that
+    /// are only read, this skips the copy `recv` makes. Returns None if the
+    /// queue is empty.
+    /// 
+    /// This is only offered on the exclusive consumer: with other consumers
+    /// around, one of them could claim and overwrite the slot while the
+    /// guard still points into it. The guard borrows the consumer mutably,
+    /// so there is at most one at a time. Leaking the guard leaves the item
+    /// at the front of the queue.
+    pub fn recv_ref(&mut self) -> Option<RecvGuard<'_, T>> {
+        let tail = self.queue.front_exclusive()?;
+        Some(RecvGuard {
+            queue: &self.queue,
+            tail,
+        })
+    }
+    
+    /// Returns the next item without removing it, or None if the queue is
+    /// empty.
+    /// 
+    /// The item stays at the front of the queue: the next `recv` returns it.
+    /// Like `recv_ref`, this relies on this being the only consumer, and
+    /// taking `&mut self` keeps the consumer from receiving while the
+    /// reference is alive. There is no `peek` on the shared queue: another
+    /// consumer could take the item and a producer reuse the slot while it
+    /// is being read, even for `Copy` items.
+    pub fn peek(&mut self) -> Option<&T> {
+        let tail = self.queue.front_exclusive()?;
+        let slot = &self.queue.buffer[self.queue.index(tail)];
+        // Published, and only this consumer can release it
+        Some(unsafe { slot.data.with(|data| (*data).assume_init_ref()) })
+    }
+    
+    /// Returns true if the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+    
+    /// Returns the approximate number of items in the queue.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+    
+    /// Returns the capacity of the queue.
+    pub fn capacity(&self) -> usize {
+        self.queue.capacity()
+    }
+}
+
+impl<T: Send> fmt::Debug for ExclusiveConsumer<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ExclusiveConsumer")
+            .field("queue", &self.queue)
+            .field("handles", &Arc::strong_count(&self.queue))
+            .finish()
+    }
+}
+
+impl<T> Drop for ExclusiveConsumer<T> {
+    fn drop(&mut self) {
+        self.queue.consumers.fetch_sub(1, Ordering::Release);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Send> IntoIterator for Consumer<T> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    /// Converts the consumer into a blocking iterator.
+    ///
+    /// See `IntoIter` for the termination conditions.
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { consumer: self }
+    }
+}
+
+/// Receives every item left in an owned queue, in order.
+/// 
+/// With the queue owned there are no other handles, so this walks from the
+/// tail to the head directly; like `Drop`, it steps over slots a producer
+/// claimed but never wrote instead of stopping at them.
+impl<T: Send> IntoIterator for MpmcQueue<T> {
+    type Item = T;
+    type IntoIter = QueueIntoIter<T>;
+    
+    fn into_iter(self) -> QueueIntoIter<T> {
+        QueueIntoIter { queue: self }
+    }
+}
+
+/// An iterator that moves the items out of an owned `MpmcQueue`.
+/// 
+/// Created by `MpmcQueue::into_iter`. Items not yet yielded are dropped
+/// together with the iterator.
+pub struct QueueIntoIter<T> {
+    queue: MpmcQueue<T>,
+}
+
+impl<T: Send> Iterator for QueueIntoIter<T> {
+    type Item = T;
+    
+    fn next(&mut self) -> Option<T> {
+        let queue = &self.queue;
+        let head = queue.producer_pos.head.load(Ordering::Relaxed);
+        loop {
+            let tail = queue.consumer_pos.tail.load(Ordering::Relaxed);
+            if tail == head {
+                return None;
+            }
+            // Step past the slot first, so `Drop` never sees it again
+            queue.consumer_pos.tail.store(next_seq(tail), Ordering::Relaxed);
+            if let Some(slot) = queue.owned_item(tail) {
+                queue.count_received(1);
+                return Some(unsafe { slot.take() });
+            }
+        }
+    }
+    
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.queue.occupancy()))
+    }
+}
+
+/// A blocking iterator over the items received by a `Consumer`.
+///
+/// `next` waits for the next item and returns None only at end-of-stream:
+/// once every `Producer` handle has been dropped and the queue has been
+/// drained. Items already in the queue when the last producer drops are still
+/// yielded. A queue that never had a `Producer` ends as soon as it is empty,
+/// and items sent directly through the queue rather than a `Producer` do not
+/// keep the iterator alive. This mirrors `std::sync::mpsc::Receiver`'s
+/// `IntoIterator`, so `for item in consumer { ... }` ends naturally.
+///
+/// The consumer handle is dropped together with the iterator.
+#[cfg(feature = "std")]
+pub struct IntoIter<T> {
+    consumer: Consumer<T>,
+}
+
+#[cfg(feature = "std")]
+impl<T: Send> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.consumer.recv_disconnected().ok()
+    }
+}
+
+/// Slots claimed by `MpmcQueue::reserve`, published by `commit`.
+pub struct WriteReservation<'a, T> {
+    queue: &'a MpmcQueue<T>,
+    head: usize,
+    len: usize,
+    filled: usize,
+}
+
+impl<T> WriteReservation<'_, T> {
+    /// Returns the number of reserved slots.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    
+    /// Returns true if no slots were reserved.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    
+    /// Returns the number of slots not yet written.
+    pub fn remaining(&self) -> usize {
+        self.len - self.filled
+    }
+    
+    /// Writes the next item into the reservation, handing it back if every
+    /// reserved slot is already written.
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        if self.filled == self.len {
+            return Err(item);
+        }
+        let slot = &self.queue.buffer[self.queue.index(advance(self.head, self.filled))];
+        slot.set_producer(NO_PRODUCER);
+        unsafe {
+            slot.data.with_mut(|data| (*data).write(item));
+        }
+        self.filled += 1;
+        Ok(())
+    }
+    
+    /// Publishes the written items to consumers, in order.
+    /// 
+    /// Slots left unwritten are released as abandoned.
+    pub fn commit(self) {
+        let this = core::mem::ManuallyDrop::new(self);
+        for i in 0..this.filled {
+            let pos = advance(this.head, i);
+            this.queue.buffer[this.queue.index(pos)]
+                .sequence
+                .store(word(next_seq(pos)), Ordering::Release);
+        }
+        this.queue.count_sent(this.filled);
+        // Abandons the rest and wakes consumers
+        drop(Abandon {
+            queue: this.queue,
+            next: advance(this.head, this.filled),
+            end: advance(this.head, this.len),
+            multiple: this.len > 1,
+        });
+    }
+}
+
+impl<T> Drop for WriteReservation<'_, T> {
+    fn drop(&mut self) {
+        for i in 0..self.filled {
+            let slot = &self.queue.buffer[self.queue.index(advance(self.head, i))];
+            unsafe {
+                slot.discard();
+            }
         }
+        drop(Abandon {
+            queue: self.queue,
+            next: self.head,
+            end: advance(self.head, self.len),
+            multiple: self.len > 1,
+        });
     }
 }
 
+/// A borrowed item at the front of the queue, returned by
+/// `ExclusiveConsumer::recv_ref`.
+/// 
+/// Dropping the guard drops the item and releases its slot.
+pub struct RecvGuard<'a, T> {
+    queue: &'a MpmcQueue<T>,
+    tail: usize,
+}
+
+impl<T> Deref for RecvGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        let slot = &self.queue.buffer[self.queue.index(self.tail)];
+        // Published and not yet released, and no other consumer exists
+        unsafe { slot.data.with(|data| (*data).assume_init_ref()) }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for RecvGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl<T> Drop for RecvGuard<'_, T> {
+    fn drop(&mut self) {
+        let slot = &self.queue.buffer[self.queue.index(self.tail)];
+        unsafe {
+            slot.discard();
+        }
+        self.queue.count_received(1);
+        self.queue.release_exclusive(self.tail);
+    }
+}
+
+/// Reports whether an item sent with `send_tracked` has been received.
+pub struct AckToken<'a, T> {
+    queue: &'a MpmcQueue<T>,
+    pos: usize,
+}
+
+impl<T> AckToken<'_, T> {
+    /// Returns true once a consumer has received the item.
+    pub fn is_acked(&self) -> bool {
+        let seq = self.queue.buffer[self.queue.index(self.pos)]
+            .sequence
+            .load(Ordering::Acquire);
+        // Releasing the slot sets it to `free_seq`, and later laps only move
+        // it further ahead
+        compare(seq, free_seq(self.pos, self.queue.capacity)).is_ge()
+    }
+    
+    /// Waits until a consumer has received the item, backing off from
+    /// spinning to short sleeps.
+    #[cfg(feature = "std")]
+    pub fn wait_acked(&self) {
+        let mut attempt = 0u32;
+        while !self.is_acked() {
+            wait_backoff(&mut attempt);
+        }
+    }
+}
+
+impl<T> fmt::Debug for AckToken<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AckToken")
+            .field("position", &self.pos)
+            .field("acked", &self.is_acked())
+            .finish()
+    }
+}
+
+/// Iterator returned by `MpmcQueue::drain`.
+pub struct Drain<'a, T> {
+    queue: &'a MpmcQueue<T>,
+}
+
+impl<T: Send> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.queue.recv()
+    }
+}
+
+/// Iterator returned by `Consumer::try_iter`.
+pub struct TryIter<'a, T> {
+    consumer: &'a Consumer<T>,
+}
+
+impl<T: Send> Iterator for TryIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.consumer.recv()
+    }
+}
+
+/// Creates a queue of the given capacity and returns a producer and a
+/// consumer for it.
+///
+/// The queue lives behind an `Arc` shared by the handles; clone them for
+/// more producers or consumers. Capacity is rounded up as in
+/// `MpmcQueue::new`.
+pub fn channel<T: Send>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+    let queue = Arc::new(MpmcQueue::new(capacity));
+    (Producer::new(Arc::clone(&queue)), Consumer::new(queue))
+}
+
+/// The sending half of a `bounded` channel: a `Producer`, named as in
+/// `std::sync::mpsc`.
+pub type Sender<T> = Producer<T>;
+
+/// The receiving half of a `bounded` channel: a `Consumer`, named as in
+/// `std::sync::mpsc`.
+pub type Receiver<T> = Consumer<T>;
+
+/// Creates a channel of the given capacity, like `channel`.
+///
+/// Neither handle gives access to the queue itself, so a `Sender` can only
+/// send and a `Receiver` can only receive; building handles from a shared
+/// `Arc<MpmcQueue<T>>` instead leaves every operation reachable through the
+/// queue.
+pub fn bounded<T: Send>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    channel(capacity)
+}
+
+/// Returns the capacity `MpmcQueue::new(requested)` ends up with: the next
+/// power of 2, and at least 2.
+///
+/// Usable in const contexts, e.g. to check a queue's ring against a memory
+/// budget at compile time:
+///
+/// ```
+/// use mpmc_std::{capacity_for, MpmcQueue};
+///
+/// const SLOTS: usize = capacity_for(1000);
+/// const _: () = assert!(SLOTS * MpmcQueue::<u64>::slot_size() <= 1 << 20);
+/// ```
+///
+/// Panics if `requested` is 0 or more than `seq::MAX_CAPACITY` once
+/// rounded up, which fails the build when evaluated in a const.
+pub const fn capacity_for(requested: usize) -> usize {
+    assert!(requested > 0, "Capacity must be greater than 0");
+    match requested.checked_next_power_of_two() {
+        Some(capacity) if capacity <= seq::MAX_CAPACITY => {
+            if capacity < 2 {
+                2
+            } else {
+                capacity
+            }
+        }
+        _ => panic!("Capacity is too large for the sequence numbers"),
+    }
+}
+
+/// Creates a queue and wires up producer and consumer handles in one expression.
+///
+/// `mpmc!(capacity: 1024, producers: 4, consumers: 4)` evaluates to
+/// `(Vec<Producer<T>>, Vec<Consumer<T>>)`, all sharing a single
+/// `Arc<MpmcQueue<T>>`. The element type is inferred from how the handles are
+/// used. This expands to the regular `MpmcQueue::new`, `Producer::new` and
+/// `Consumer::new` constructors, so there is no runtime cost over writing the
+/// wiring by hand.
+#[macro_export]
+macro_rules! mpmc {
+    (capacity: $capacity:expr, producers: $producers:expr, consumers: $consumers:expr $(,)?) => {{
+        let queue = $crate::__private::Arc::new($crate::MpmcQueue::new($capacity));
+        let producers: $crate::__private::Vec<_> = (0..$producers)
+            .map(|_| $crate::Producer::new($crate::__private::Arc::clone(&queue)))
+            .collect();
+        let consumers: $crate::__private::Vec<_> = (0..$consumers)
+            .map(|_| $crate::Consumer::new($crate::__private::Arc::clone(&queue)))
+            .collect();
+        (producers, consumers)
+    }};
+}
+
+// Used by `mpmc!`, which must expand to the same paths with or without std
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::sync::Arc;
+    pub use alloc::vec::Vec;
+}
+
+pub use aligned::Aligned;
+pub use array_queue::ArrayMpmcQueue;
+#[cfg(feature = "std")]
+pub use broadcast_queue::{BroadcastConsumer, BroadcastQueue};
+#[cfg(feature = "std")]
+pub use buffered::BufferedProducer;
+pub use byte_ring::ByteRing;
+#[cfg(feature = "std")]
+pub use dedup_queue::DedupMpmcQueue;
+#[cfg(feature = "std")]
+pub use delay_queue::DelayQueue;
+#[cfg(feature = "std")]
+pub use elastic_queue::ElasticMpmcQueue;
+pub use error::{
+    CapacityError, DedupSendError, RecvError, RecvTimeoutError, SendError, SendTimeoutError, TryRecvError, TrySendError,
+};
+pub use fair::FairConsumer;
+#[cfg(feature = "std")]
+pub use overflow_queue::OverflowMpmcQueue;
+pub use packed_queue::PackedMpmcQueue;
+pub use prefetch::PrefetchConsumer;
+#[cfg(feature = "std")]
+pub use rate_limited::RateLimitedConsumer;
+#[cfg(feature = "std")]
+pub use select::Select;
+#[cfg(feature = "std")]
+pub use sharded_queue::ShardedMpmcQueue;
+#[cfg(target_has_atomic = "64")]
+pub use stack::{MpmcStack, StackConsumer, StackProducer};
+#[cfg(feature = "futures")]
+pub use sink::ProducerSink;
+#[cfg(feature = "std")]
+pub use ttl_queue::TtlMpmcQueue;
+#[cfg(feature = "std")]
+pub use wait::{set_wake_priority, WakePolicy};
+pub use wait_strategy::{Backoff, BusySpin, WaitStrategy};
+#[cfg(feature = "std")]
+pub use wait_strategy::{Sleeping, Yielding};
+pub use work_stealing::{Steal, Stealer, WorkStealingDeque};
+
 // Re-export SIMD optimized queue when feature is enabled
 #[cfg(feature = "simd")]
-pub use simd_queue::{SimdMpmcQueue, SimdProducer, SimdConsumer};
\ No newline at end of file
+pub use simd_queue::{SimdMpmcQueue, SimdProducer, SimdConsumer};
+#[cfg(feature = "simd")]
+pub use simd32_queue::{Simd32Bit, Simd32MpmcQueue};
\ No newline at end of file
@@ -0,0 +1,144 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::{capacity_for, MpmcQueue};
+
+/// MPMC queue that absorbs bursts with a single on-demand overflow segment.
+///
+/// This is a middle ground between a strictly bounded queue and a fully
+/// growable one. When the primary ring is full, further items spill into one
+/// additional ring of `overflow_capacity` slots. The overflow segment is only
+/// allocated the first time it is needed and is freed again once it has been
+/// drained, so a quiet queue costs no more than a plain `MpmcQueue`.
+///
+/// Ordering:
+/// - Consumers always drain the primary ring first, then the overflow segment
+/// - Items that spilled into the overflow can therefore be overtaken by items
+///   sent later into space that has since been freed in the primary ring
+/// - FIFO order is preserved within each segment
+///
+/// Once both the primary ring and the overflow segment are full, `send`
+/// fails just like `MpmcQueue::send`.
+pub struct OverflowMpmcQueue<T> {
+    primary: MpmcQueue<T>,
+    overflow: Mutex<Option<Arc<MpmcQueue<T>>>>,
+    overflow_active: AtomicBool,
+    overflow_capacity: usize,
+}
+
+impl<T: Send> OverflowMpmcQueue<T> {
+    /// Creates a queue with a primary ring of `primary_capacity` slots and an
+    /// overflow segment of `overflow_capacity` slots allocated on demand.
+    ///
+    /// Both capacities are rounded up as in `MpmcQueue::new`.
+    pub fn new_with_overflow_segment(primary_capacity: usize, overflow_capacity: usize) -> Self {
+        assert!(overflow_capacity > 0, "Overflow capacity must be greater than 0");
+
+        Self {
+            primary: MpmcQueue::new(primary_capacity),
+            overflow: Mutex::new(None),
+            overflow_active: AtomicBool::new(false),
+            // The size the segment will be allocated with
+            overflow_capacity: capacity_for(overflow_capacity),
+        }
+    }
+
+    /// Attempts to send an item, spilling into the overflow segment when the
+    /// primary ring is full.
+    ///
+    /// Returns the item back if both the primary ring and the overflow
+    /// segment are full.
+    pub fn send(&self, item: T) -> Result<(), T> {
+        match self.primary.send(item) {
+            Ok(()) => Ok(()),
+            Err(item) => {
+                // Take a reference to the segment (allocating it if needed)
+                // while holding the lock, so it cannot be freed under us
+                let segment = {
+                    let mut overflow = self.overflow.lock().unwrap();
+                    let segment = overflow.get_or_insert_with(|| {
+                        Arc::new(MpmcQueue::new(self.overflow_capacity))
+                    });
+                    self.overflow_active.store(true, Ordering::Release);
+                    Arc::clone(segment)
+                };
+                segment.send(item)
+            }
+        }
+    }
+
+    /// Attempts to receive an item, draining the primary ring before the
+    /// overflow segment.
+    ///
+    /// Returns None if both are empty.
+    pub fn recv(&self) -> Option<T> {
+        if let Some(item) = self.primary.recv() {
+            return Some(item);
+        }
+
+        // Fast path: no overflow segment, avoid taking the lock
+        if !self.overflow_active.load(Ordering::Acquire) {
+            return None;
+        }
+
+        let segment = self.overflow.lock().unwrap().as_ref().map(Arc::clone)?;
+        let item = segment.recv();
+        drop(segment);
+
+        self.release_drained_segment();
+        item
+    }
+
+    /// Frees the overflow segment if it is empty and nobody else holds it.
+    fn release_drained_segment(&self) {
+        let mut overflow = self.overflow.lock().unwrap();
+        if let Some(segment) = overflow.as_ref() {
+            // Producers and consumers only obtain the segment under this lock,
+            // so a strong count of 1 means no send/recv is in flight on it
+            if Arc::strong_count(segment) == 1 && segment.is_empty() {
+                *overflow = None;
+                self.overflow_active.store(false, Ordering::Release);
+            }
+        }
+    }
+
+    /// Returns the capacity of the primary ring.
+    pub fn capacity(&self) -> usize {
+        self.primary.capacity()
+    }
+
+    /// Returns the capacity of the overflow segment.
+    pub fn overflow_capacity(&self) -> usize {
+        self.overflow_capacity
+    }
+
+    /// Returns true if the overflow segment is currently allocated.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn has_overflow_segment(&self) -> bool {
+        self.overflow_active.load(Ordering::Acquire)
+    }
+
+    /// Returns the approximate number of items across both segments.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn len(&self) -> usize {
+        let overflow_len = if self.has_overflow_segment() {
+            self.overflow
+                .lock()
+                .unwrap()
+                .as_ref()
+                .map_or(0, |segment| segment.len())
+        } else {
+            0
+        };
+        self.primary.len() + overflow_len
+    }
+
+    /// Returns true if both segments are empty.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
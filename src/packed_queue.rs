@@ -0,0 +1,322 @@
+//! Bounded MPMC queue with densely packed slots.
+
+use alloc::boxed::Box;
+#[cfg(not(loom))]
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::sync::atomic::Ordering;
+
+#[cfg(not(loom))]
+use crate::seq::advance;
+use crate::seq::{compare, depth, free_seq, next_seq, word};
+use crate::sync::{AtomicSeq, AtomicUsize, UnsafeCell};
+use crate::{capacity_for, ConsumerPos, ProducerPos, Slot};
+
+/// A bounded MPMC queue that stores items and sequence numbers in two
+/// packed arrays instead of one cache-line aligned slot per item.
+///
+/// `MpmcQueue` pads every slot to a cache line, so a queue of `u8`s spends
+/// 64 bytes per item. Here the items sit back to back in one array and the
+/// sequence numbers in another, so a slot costs `size_of::<T>()` plus one
+/// sequence number, and only the head and tail keep a cache line each.
+/// The protocol is the same: a slot's sequence number says whether it is
+/// free for, or holds the item of, a given position.
+///
+/// The price is false sharing between neighbouring slots: producers and
+/// consumers working on adjacent positions write to the same cache lines,
+/// which costs throughput under heavy contention. Prefer it for many small,
+/// mostly idle queues, where memory matters more than peak throughput.
+///
+/// Only the non-blocking operations are provided, plus
+/// `recv_zero_copy_wrapping`, which the packed items make possible.
+pub struct PackedMpmcQueue<T> {
+    sequences: Box<[AtomicSeq]>,
+    data: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    mask: usize,
+    producer_pos: ProducerPos,
+    consumer_pos: ConsumerPos,
+}
+
+impl<T> PackedMpmcQueue<T> {
+    /// Creates a queue; capacity is rounded up to a power of 2 as in
+    /// `MpmcQueue::new`.
+    ///
+    /// Panics if `capacity` is 0 or more than `seq::MAX_CAPACITY` once
+    /// rounded up.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity_for(capacity);
+
+        Self {
+            sequences: (0..capacity).map(|i| AtomicSeq::new(word(i))).collect(),
+            data: (0..capacity).map(|_| UnsafeCell::new(MaybeUninit::uninit())).collect(),
+            mask: capacity - 1,
+            producer_pos: ProducerPos {
+                head: AtomicUsize::new(0),
+            },
+            consumer_pos: ConsumerPos {
+                tail: AtomicUsize::new(0),
+            },
+        }
+    }
+
+    /// Attempts to send an item, handing it back if the queue is full.
+    ///
+    /// The slot a send needs counts as full until the consumer of its last
+    /// item has released it; for items borrowed with
+    /// `recv_zero_copy_wrapping`, that is when the guard drops.
+    pub fn send(&self, item: T) -> Result<(), T> {
+        loop {
+            let head = self.producer_pos.head.load(Ordering::Relaxed);
+            let index = head & self.mask;
+            let seq = self.sequences[index].load(Ordering::Acquire);
+
+            match compare(seq, head) {
+                core::cmp::Ordering::Equal => {
+                    if self
+                        .producer_pos
+                        .head
+                        .compare_exchange_weak(head, next_seq(head), Ordering::Relaxed, Ordering::Relaxed)
+                        .is_ok()
+                    {
+                        unsafe {
+                            self.data[index].with_mut(|data| (*data).write(item));
+                        }
+                        self.sequences[index].store(word(next_seq(head)), Ordering::Release);
+                        return Ok(());
+                    }
+                }
+                core::cmp::Ordering::Less => {
+                    // The slot still holds last lap's item: the queue is full,
+                    // or a consumer claimed the item and has not released the
+                    // slot, which a guard may not do for a long time
+                    return Err(item);
+                }
+                core::cmp::Ordering::Greater => {
+                    // Another producer claimed `head`, wait until we see it move
+                    while self.producer_pos.head.load(Ordering::Acquire) == head {
+                        core::hint::spin_loop();
+                    }
+                    continue;
+                }
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Attempts to receive an item, returning None if the queue is empty.
+    pub fn recv(&self) -> Option<T> {
+        loop {
+            let tail = self.consumer_pos.tail.load(Ordering::Relaxed);
+            let index = tail & self.mask;
+            let seq = self.sequences[index].load(Ordering::Acquire);
+
+            match compare(seq, next_seq(tail)) {
+                core::cmp::Ordering::Equal => {
+                    if self
+                        .consumer_pos
+                        .tail
+                        .compare_exchange_weak(tail, next_seq(tail), Ordering::Relaxed, Ordering::Relaxed)
+                        .is_ok()
+                    {
+                        // Scrubbed like a slot's storage under `secure`
+                        let item = self.data[index].with_mut(|data| unsafe {
+                            let item = (*data).assume_init_read();
+                            Slot::scrub(data);
+                            item
+                        });
+                        self.sequences[index].store(word(free_seq(tail, self.capacity())), Ordering::Release);
+                        return Some(item);
+                    }
+                }
+                core::cmp::Ordering::Less => return None,
+                core::cmp::Ordering::Greater => {
+                    // Another consumer claimed `tail`, wait until we see it move
+                    while self.consumer_pos.tail.load(Ordering::Acquire) == tail {
+                        core::hint::spin_loop();
+                    }
+                    continue;
+                }
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Borrows up to `max` items from the front of the queue in place,
+    /// returning None if the queue is empty.
+    ///
+    /// Every item ready at the front, up to `max`, is claimed with one CAS,
+    /// and the guard views them as two slices, like `VecDeque::as_slices`:
+    /// the run up to the end of the ring and the rest from its start. The
+    /// second slice is empty unless the run wraps around. Dropping the guard
+    /// drops the items and releases all of their slots, in both segments.
+    ///
+    /// Other consumers carry on past the claimed run while the guard is
+    /// held, but producers cannot reuse its slots, so a long-lived guard
+    /// makes the queue look full.
+    ///
+    /// Not available under loom, whose cells cannot be viewed as a slice.
+    #[cfg(not(loom))]
+    pub fn recv_zero_copy_wrapping(&self, max: usize) -> Option<RecvSliceGuard<'_, T>> {
+        let max = max.min(self.capacity());
+        if max == 0 {
+            return None;
+        }
+
+        loop {
+            let tail = self.consumer_pos.tail.load(Ordering::Relaxed);
+            let seq = self.sequences[tail & self.mask].load(Ordering::Acquire);
+
+            match compare(seq, next_seq(tail)) {
+                core::cmp::Ordering::Equal => {
+                    // The ready run only grows until our CAS, and no other
+                    // consumer can take it from under us once the CAS lands
+                    let len = 1 + (1..max)
+                        .take_while(|&i| {
+                            let pos = advance(tail, i);
+                            let seq = self.sequences[pos & self.mask].load(Ordering::Acquire);
+                            compare(seq, next_seq(pos)).is_eq()
+                        })
+                        .count();
+                    if self
+                        .consumer_pos
+                        .tail
+                        .compare_exchange_weak(tail, advance(tail, len), Ordering::Relaxed, Ordering::Relaxed)
+                        .is_ok()
+                    {
+                        return Some(RecvSliceGuard {
+                            queue: self,
+                            tail,
+                            len,
+                            _items: PhantomData,
+                        });
+                    }
+                }
+                core::cmp::Ordering::Less => return None,
+                core::cmp::Ordering::Greater => {
+                    // Another consumer claimed `tail`, wait until we see it move
+                    while self.consumer_pos.tail.load(Ordering::Acquire) == tail {
+                        core::hint::spin_loop();
+                    }
+                    continue;
+                }
+            }
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Returns the capacity of the queue.
+    pub const fn capacity(&self) -> usize {
+        self.mask + 1
+    }
+
+    /// Returns the bytes of the two arrays backing the queue.
+    ///
+    /// Compare with `MpmcQueue::memory_footprint`.
+    pub const fn memory_footprint(&self) -> usize {
+        self.capacity() * (core::mem::size_of::<T>() + core::mem::size_of::<AtomicSeq>())
+    }
+
+    /// Returns the approximate number of items in the queue.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn len(&self) -> usize {
+        // Tail first, so a consumer racing ahead cannot make the depth
+        // wrap; clamped since producers may move on in between too
+        let tail = self.consumer_pos.tail.load(Ordering::Acquire);
+        let head = self.producer_pos.head.load(Ordering::Acquire);
+        depth(head, tail).min(self.capacity())
+    }
+
+    /// Returns true if the queue is empty.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true if the queue is full.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn is_full(&self) -> bool {
+        self.len() >= self.capacity()
+    }
+}
+
+unsafe impl<T: Send> Send for PackedMpmcQueue<T> {}
+unsafe impl<T: Send> Sync for PackedMpmcQueue<T> {}
+
+impl<T> Drop for PackedMpmcQueue<T> {
+    fn drop(&mut self) {
+        while self.recv().is_some() {}
+    }
+}
+
+/// Items borrowed in place from the front of a `PackedMpmcQueue`, returned
+/// by `recv_zero_copy_wrapping`.
+///
+/// Dropping the guard drops the items and releases their slots.
+#[cfg(not(loom))]
+pub struct RecvSliceGuard<'a, T> {
+    queue: &'a PackedMpmcQueue<T>,
+    tail: usize,
+    len: usize,
+    // Hands out `&T`s and drops the items wherever the guard goes
+    _items: PhantomData<&'a [T]>,
+}
+
+#[cfg(not(loom))]
+impl<T> RecvSliceGuard<'_, T> {
+    /// Returns the borrowed items as two slices, in queue order.
+    ///
+    /// The first runs from the front of the queue up to the end of the ring
+    /// at most; the second holds the rest of the items from the start of
+    /// the ring, and is empty unless the run wraps around.
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let start = self.tail & self.queue.mask;
+        let first = self.len.min(self.queue.capacity() - start);
+        // Cells are transparent over `MaybeUninit<T>`, which is over `T`;
+        // the claimed slots are published and ours until the guard drops
+        let items = self.queue.data.as_ptr().cast::<T>();
+        unsafe {
+            (
+                core::slice::from_raw_parts(items.add(start), first),
+                core::slice::from_raw_parts(items, self.len - first),
+            )
+        }
+    }
+
+    /// Returns the number of borrowed items, across both slices.
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no items are borrowed; never the case for a guard
+    /// returned by `recv_zero_copy_wrapping`.
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+#[cfg(not(loom))]
+impl<T: core::fmt::Debug> core::fmt::Debug for RecvSliceGuard<'_, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let (first, second) = self.as_slices();
+        f.debug_list().entries(first).entries(second).finish()
+    }
+}
+
+#[cfg(not(loom))]
+impl<T> Drop for RecvSliceGuard<'_, T> {
+    fn drop(&mut self) {
+        for i in 0..self.len {
+            let pos = advance(self.tail, i);
+            let index = pos & self.queue.mask;
+            // Scrubbed like a slot's storage under `secure`
+            self.queue.data[index].with_mut(|data| unsafe {
+                (*data).assume_init_drop();
+                Slot::scrub(data);
+            });
+            self.queue.sequences[index].store(word(free_seq(pos, self.queue.capacity())), Ordering::Release);
+        }
+    }
+}
@@ -0,0 +1,210 @@
+//! Hardware performance counters for benchmark analysis (Linux only).
+//!
+//! Wraps `perf_event_open(2)` to count cache misses and stalled cycles over a
+//! measured region, so the false-sharing and cache-miss behaviour the queue's
+//! cache-line padding is designed to avoid can be checked with hard numbers.
+//! This is an analysis tool for the benchmark harness, not something to enable
+//! in production builds.
+//!
+//! Requirements:
+//! - Linux, with the `perf` feature enabled
+//! - `/proc/sys/kernel/perf_event_paranoid` set to 2 or lower (user-space
+//!   counting of your own process), or the `CAP_PERFMON` capability
+//! - Hardware counters exposed to the machine; many VMs and containers do not
+//!   provide them, in which case `PerfCounters::new` returns an error
+//!
+//! Counters are opened with `inherit` set, so threads spawned after
+//! `PerfCounters::new` are counted as well. Individual events the CPU does
+//! not support (stalled-cycle events are often missing) are reported as None.
+
+use std::io;
+
+// perf_event_attr as defined in linux/perf_event.h, up to PERF_ATTR_SIZE_VER6
+// (120 bytes); the kernel zero-fills the fields of later versions
+#[repr(C)]
+#[derive(Default)]
+struct PerfEventAttr {
+    type_: u32,
+    size: u32,
+    config: u64,
+    sample_period: u64,
+    sample_type: u64,
+    read_format: u64,
+    flags: u64,
+    wakeup_events: u32,
+    bp_type: u32,
+    config1: u64,
+    config2: u64,
+    branch_sample_type: u64,
+    sample_regs_user: u64,
+    sample_stack_user: u32,
+    clockid: i32,
+    sample_regs_intr: u64,
+    aux_watermark: u32,
+    sample_max_stack: u16,
+    reserved_2: u16,
+    aux_sample_size: u32,
+    reserved_3: u32,
+}
+
+const _: () = assert!(std::mem::size_of::<PerfEventAttr>() == 120);
+
+const PERF_TYPE_HARDWARE: u32 = 0;
+
+const PERF_COUNT_HW_CPU_CYCLES: u64 = 0;
+const PERF_COUNT_HW_CACHE_MISSES: u64 = 3;
+const PERF_COUNT_HW_STALLED_CYCLES_FRONTEND: u64 = 7;
+const PERF_COUNT_HW_STALLED_CYCLES_BACKEND: u64 = 8;
+
+// Bits in perf_event_attr.flags
+const FLAG_DISABLED: u64 = 1 << 0;
+const FLAG_INHERIT: u64 = 1 << 1;
+const FLAG_EXCLUDE_KERNEL: u64 = 1 << 5;
+const FLAG_EXCLUDE_HV: u64 = 1 << 6;
+
+const PERF_EVENT_IOC_ENABLE: libc::c_ulong = 0x2400;
+const PERF_EVENT_IOC_DISABLE: libc::c_ulong = 0x2401;
+const PERF_EVENT_IOC_RESET: libc::c_ulong = 0x2403;
+
+/// A single opened hardware counter.
+struct Counter {
+    fd: libc::c_int,
+}
+
+impl Counter {
+    fn open(config: u64) -> io::Result<Self> {
+        let attr = PerfEventAttr {
+            type_: PERF_TYPE_HARDWARE,
+            size: std::mem::size_of::<PerfEventAttr>() as u32,
+            config,
+            flags: FLAG_DISABLED | FLAG_INHERIT | FLAG_EXCLUDE_KERNEL | FLAG_EXCLUDE_HV,
+            ..Default::default()
+        };
+
+        // Measure the calling process on any CPU, without an event group
+        let fd = unsafe {
+            libc::syscall(
+                libc::SYS_perf_event_open,
+                &attr as *const PerfEventAttr,
+                0 as libc::pid_t,
+                -1 as libc::c_int,
+                -1 as libc::c_int,
+                0 as libc::c_ulong,
+            )
+        };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Self { fd: fd as libc::c_int })
+    }
+
+    fn ioctl(&self, request: libc::c_ulong) {
+        unsafe {
+            libc::ioctl(self.fd, request, 0);
+        }
+    }
+
+    fn read(&self) -> Option<u64> {
+        let mut value = 0u64;
+        let n = unsafe {
+            libc::read(
+                self.fd,
+                &mut value as *mut u64 as *mut libc::c_void,
+                std::mem::size_of::<u64>(),
+            )
+        };
+        (n == std::mem::size_of::<u64>() as isize).then_some(value)
+    }
+}
+
+impl Drop for Counter {
+    fn drop(&mut self) {
+        unsafe {
+            libc::close(self.fd);
+        }
+    }
+}
+
+/// Counts collected over one measured region.
+///
+/// Events that could not be opened on this machine are None.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PerfReport {
+    pub cycles: Option<u64>,
+    pub cache_misses: Option<u64>,
+    pub stalled_cycles_frontend: Option<u64>,
+    pub stalled_cycles_backend: Option<u64>,
+}
+
+/// A set of hardware counters that can be started and stopped around a
+/// measured region.
+pub struct PerfCounters {
+    cycles: Option<Counter>,
+    cache_misses: Option<Counter>,
+    stalled_frontend: Option<Counter>,
+    stalled_backend: Option<Counter>,
+}
+
+impl PerfCounters {
+    /// Opens the counters for the current process.
+    ///
+    /// Fails if none of the events can be opened, typically because of
+    /// `perf_event_paranoid` restrictions or missing hardware counters.
+    pub fn new() -> io::Result<Self> {
+        let (cycles, cycles_error) = match Counter::open(PERF_COUNT_HW_CPU_CYCLES) {
+            Ok(counter) => (Some(counter), None),
+            Err(e) => (None, Some(e)),
+        };
+        let counters = Self {
+            cycles,
+            cache_misses: Counter::open(PERF_COUNT_HW_CACHE_MISSES).ok(),
+            stalled_frontend: Counter::open(PERF_COUNT_HW_STALLED_CYCLES_FRONTEND).ok(),
+            stalled_backend: Counter::open(PERF_COUNT_HW_STALLED_CYCLES_BACKEND).ok(),
+        };
+
+        match cycles_error {
+            // Surface the error from the most basic event
+            Some(e) if counters.all().all(|counter| counter.is_none()) => Err(e),
+            _ => Ok(counters),
+        }
+    }
+
+    fn all(&self) -> impl Iterator<Item = &Option<Counter>> {
+        [
+            &self.cycles,
+            &self.cache_misses,
+            &self.stalled_frontend,
+            &self.stalled_backend,
+        ]
+        .into_iter()
+    }
+
+    /// Resets and starts all counters.
+    pub fn start(&mut self) {
+        for counter in self.all().flatten() {
+            counter.ioctl(PERF_EVENT_IOC_RESET);
+            counter.ioctl(PERF_EVENT_IOC_ENABLE);
+        }
+    }
+
+    /// Stops all counters and returns the counts since `start`.
+    pub fn stop(&mut self) -> PerfReport {
+        for counter in self.all().flatten() {
+            counter.ioctl(PERF_EVENT_IOC_DISABLE);
+        }
+        PerfReport {
+            cycles: self.cycles.as_ref().and_then(Counter::read),
+            cache_misses: self.cache_misses.as_ref().and_then(Counter::read),
+            stalled_cycles_frontend: self.stalled_frontend.as_ref().and_then(Counter::read),
+            stalled_cycles_backend: self.stalled_backend.as_ref().and_then(Counter::read),
+        }
+    }
+
+    /// Runs `f` with the counters enabled and returns its result alongside
+    /// the collected counts.
+    pub fn measure<R>(&mut self, f: impl FnOnce() -> R) -> (R, PerfReport) {
+        self.start();
+        let result = f();
+        (result, self.stop())
+    }
+}
@@ -0,0 +1,117 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::{Consumer, MpmcQueue};
+
+/// Token bucket used to pace dequeuing.
+struct TokenBucket {
+    tokens: f64,
+    burst: f64,
+    rate: f64, // tokens per second
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.burst);
+        self.last_refill = now;
+    }
+
+    /// Takes a token if one is available, otherwise returns how long until
+    /// the next token is due.
+    fn try_take(&mut self) -> Result<(), Duration> {
+        self.refill();
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / self.rate))
+        }
+    }
+}
+
+/// A consumer handle that dequeues no faster than a configured rate.
+///
+/// Pacing uses a token bucket: each received item costs one token and tokens
+/// refill continuously at `items_per_sec`. The bucket holds at most `burst`
+/// tokens and starts full, so after an idle period up to `burst` items can be
+/// received back to back before pacing kicks in again. `new` uses a burst of
+/// 1, which spaces every item evenly.
+///
+/// This is useful when the queue feeds a rate-sensitive sink (e.g. a
+/// rate-limited API) and a backlog must not be flushed all at once.
+pub struct RateLimitedConsumer<T> {
+    consumer: Consumer<T>,
+    bucket: Mutex<TokenBucket>,
+}
+
+impl<T: Send> RateLimitedConsumer<T> {
+    /// Creates a rate-limited consumer with a burst size of 1.
+    pub fn new(queue: Arc<MpmcQueue<T>>, items_per_sec: u32) -> Self {
+        Self::with_burst(queue, items_per_sec, 1)
+    }
+
+    /// Creates a rate-limited consumer that allows up to `burst` items to be
+    /// received back to back after an idle period.
+    pub fn with_burst(queue: Arc<MpmcQueue<T>>, items_per_sec: u32, burst: u32) -> Self {
+        assert!(items_per_sec > 0, "Rate must be greater than 0");
+        assert!(burst > 0, "Burst must be greater than 0");
+
+        Self {
+            consumer: Consumer::new(queue),
+            bucket: Mutex::new(TokenBucket {
+                tokens: burst as f64,
+                burst: burst as f64,
+                rate: items_per_sec as f64,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Receives an item, blocking until both a token and an item are available.
+    pub fn recv(&self) -> T {
+        loop {
+            let wait = self.bucket.lock().unwrap().try_take();
+            match wait {
+                Ok(()) => break,
+                Err(wait) => std::thread::sleep(wait),
+            }
+        }
+
+        // Parks on the queue's wait list until an item arrives
+        self.consumer.recv_blocking()
+    }
+
+    /// Attempts to receive an item without blocking.
+    ///
+    /// Returns None if the rate limit is currently exhausted or the queue is
+    /// empty. A token is only spent when an item is actually received.
+    pub fn try_recv(&self) -> Option<T> {
+        let mut bucket = self.bucket.lock().unwrap();
+        bucket.refill();
+        if bucket.tokens < 1.0 {
+            return None;
+        }
+
+        let item = self.consumer.recv()?;
+        bucket.tokens -= 1.0;
+        Some(item)
+    }
+
+    /// Returns the configured rate in items per second.
+    pub fn rate(&self) -> f64 {
+        self.bucket.lock().unwrap().rate
+    }
+
+    /// Returns true if the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.consumer.is_empty()
+    }
+
+    /// Returns the approximate number of items in the queue.
+    pub fn len(&self) -> usize {
+        self.consumer.len()
+    }
+}
@@ -0,0 +1,141 @@
+//! Waiting on several queues at once.
+
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+use crate::error::RecvTimeoutError;
+use crate::wait::Registration;
+use crate::Consumer;
+
+/// Receives from whichever of several consumers has an item first.
+///
+/// Register consumers with `recv`, then call `select` to park until any of
+/// their queues has an item. The thread registers with every queue's
+/// not-empty list and sleeps, so waiting on many queues does not spin. Each
+/// call starts polling at the consumer after the one that fired last, so a
+/// busy queue cannot starve the others.
+///
+/// ```
+/// use mpmc_std::{channel, Select};
+///
+/// let (urgent_tx, urgent) = channel(16);
+/// let (bulk_tx, bulk) = channel(16);
+/// let mut select = Select::new();
+/// select.recv(&urgent);
+/// let bulk_index = select.recv(&bulk);
+///
+/// bulk_tx.send(1).unwrap();
+/// assert_eq!(select.select(), (bulk_index, 1));
+/// # drop(urgent_tx);
+/// ```
+pub struct Select<'a, T> {
+    consumers: Vec<&'a Consumer<T>>,
+    next: usize,
+}
+
+impl<'a, T: Send> Select<'a, T> {
+    /// Creates a selector with no consumers.
+    pub fn new() -> Self {
+        Self {
+            consumers: Vec::new(),
+            next: 0,
+        }
+    }
+
+    /// Adds a consumer and returns its index, as reported by `select`.
+    pub fn recv(&mut self, consumer: &'a Consumer<T>) -> usize {
+        self.consumers.push(consumer);
+        self.consumers.len() - 1
+    }
+
+    /// Receives from the first consumer with an item, without waiting.
+    pub fn try_select(&mut self) -> Option<(usize, T)> {
+        (0..self.consumers.len()).find_map(|offset| self.attempt(offset))
+    }
+
+    /// Parks until one of the consumers has an item, and returns its index
+    /// and the item.
+    ///
+    /// Waits forever if no consumer was added.
+    pub fn select(&mut self) -> (usize, T) {
+        match self.select_deadline(None) {
+            Some(selected) => selected,
+            None => unreachable!("selects without a deadline only end on success"),
+        }
+    }
+
+    /// Like `select`, but gives up after `timeout`.
+    pub fn select_timeout(&mut self, timeout: Duration) -> Result<(usize, T), RecvTimeoutError> {
+        self.select_deadline(Instant::now().checked_add(timeout))
+            .ok_or(RecvTimeoutError::Timeout)
+    }
+
+    fn select_deadline(&mut self, deadline: Option<Instant>) -> Option<(usize, T)> {
+        if let Some(selected) = self.try_select() {
+            return Some(selected);
+        }
+
+        // Every queue wakes this thread through the same waker. Registering
+        // before each final attempt means an item sent in between either
+        // shows up in the attempt or unparks us.
+        let waker = Waker::from(Arc::new(Unparker(thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        let consumers = self.consumers.clone();
+        let mut registrations: Vec<_> = consumers
+            .iter()
+            .map(|consumer| Registration::new(&consumer.queue.not_empty))
+            .collect();
+
+        loop {
+            for offset in 0..consumers.len() {
+                let index = (self.next + offset) % consumers.len();
+                let consumer = consumers[index];
+                if let Poll::Ready(item) = registrations[index].poll_until(&mut cx, || consumer.recv()) {
+                    self.next = index + 1;
+                    // Dropping the other registrations passes on any wakeup
+                    // they already took, so no other waiter misses an item
+                    return Some((index, item));
+                }
+            }
+
+            match deadline {
+                None => thread::park(),
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return None;
+                    }
+                    thread::park_timeout(deadline - now);
+                }
+            }
+        }
+    }
+
+    fn attempt(&mut self, offset: usize) -> Option<(usize, T)> {
+        let index = (self.next + offset) % self.consumers.len();
+        let item = self.consumers[index].recv()?;
+        self.next = index + 1;
+        Some((index, item))
+    }
+}
+
+impl<T: Send> Default for Select<'_, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Wakes the selecting thread.
+struct Unparker(Thread);
+
+impl Wake for Unparker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
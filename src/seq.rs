@@ -0,0 +1,85 @@
+//! Wrapping arithmetic for positions and slot sequence numbers.
+//!
+//! Producer (`head`) and consumer (`tail`) positions are free-running
+//! counters that wrap around at `usize::MAX`, and slot sequence numbers are
+//! derived from them. All of the protocol's arithmetic on them goes through
+//! these helpers, so the wrapping behaviour lives (and is tested) in one place.
+//!
+//! For a slot at buffer index `pos & mask`:
+//! - `sequence == pos`: free, a producer may write position `pos`
+//! - `sequence == next_seq(pos)`: holds the item written at `pos`
+//! - `sequence == free_seq(pos, capacity)`: consumed, free for position
+//!   `pos + capacity`
+//!
+//! Slots store sequence numbers as `SeqWord`s, which with the `compact-seq`
+//! feature keep only the low 32 bits. Positions are never truncated, so
+//! every claim still goes through a CAS on the full `usize` position, and a
+//! stored sequence number is only ever compared with one at most
+//! `capacity` away from it: `compare` orders them by their wrapping
+//! distance, which is exact as long as `capacity <= MAX_CAPACITY`.
+
+use core::cmp::Ordering;
+
+/// A slot sequence number as stored in the slot: `usize`, or `u32` with
+/// the `compact-seq` feature.
+#[cfg(not(feature = "compact-seq"))]
+pub type SeqWord = usize;
+/// A slot sequence number as stored in the slot: `usize`, or `u32` with
+/// the `compact-seq` feature.
+#[cfg(feature = "compact-seq")]
+pub type SeqWord = u32;
+
+#[cfg(not(feature = "compact-seq"))]
+type SeqDistance = isize;
+#[cfg(feature = "compact-seq")]
+type SeqDistance = i32;
+
+/// Largest capacity whose sequence numbers `compare` can still order:
+/// half the range of `SeqWord`.
+pub const MAX_CAPACITY: usize = SeqDistance::MAX as usize + 1;
+
+/// Number of positions between `tail` and `head`, i.e. `head - tail`
+/// modulo `usize::MAX + 1`.
+///
+/// Correct across the wrap as long as `head` is at most `usize::MAX`
+/// positions ahead of `tail`, which always holds for a queue's positions.
+#[inline]
+pub const fn depth(head: usize, tail: usize) -> usize {
+    head.wrapping_sub(tail)
+}
+
+/// Position `n` steps after `pos`, wrapping at `usize::MAX`.
+#[inline]
+pub const fn advance(pos: usize, n: usize) -> usize {
+    pos.wrapping_add(n)
+}
+
+/// Sequence number that marks the slot for `pos` as holding data.
+///
+/// This is also the position that follows `pos`.
+#[inline]
+pub const fn next_seq(pos: usize) -> usize {
+    advance(pos, 1)
+}
+
+/// Sequence number that marks the slot for `pos` as consumed, making it
+/// available to the producer of position `pos + capacity`.
+#[inline]
+pub const fn free_seq(pos: usize, capacity: usize) -> usize {
+    advance(pos, capacity)
+}
+
+/// The sequence number a slot stores for `seq`, truncated to `SeqWord`.
+#[inline]
+pub const fn word(seq: usize) -> SeqWord {
+    seq as SeqWord
+}
+
+/// Orders a slot's stored sequence number against the expected `seq`
+/// across the wrap of `SeqWord`: `Less` means the slot is still behind.
+///
+/// Exact while the two are less than `MAX_CAPACITY` apart.
+#[inline]
+pub fn compare(stored: SeqWord, seq: usize) -> Ordering {
+    (stored.wrapping_sub(word(seq)) as SeqDistance).cmp(&0)
+}
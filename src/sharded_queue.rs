@@ -0,0 +1,127 @@
+use std::cell::Cell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::MpmcQueue;
+
+thread_local! {
+    // Per-thread round-robin position, so producers spread over the shards
+    // without touching shared state
+    static NEXT_SHARD: Cell<usize> = const { Cell::new(0) };
+}
+
+/// MPMC queue split into independent shards to cut contention.
+///
+/// Holds `shards` separate `MpmcQueue`s. Each producer thread cycles through
+/// the shards on its own, and consumers scan them starting from a rotating
+/// index, so threads mostly hit different head and tail positions instead
+/// of all racing on one pair.
+///
+/// The price is ordering: FIFO holds within a shard but not across shards,
+/// so items can be received in a different order than they were sent. That
+/// suits work queues where any order will do.
+///
+/// `send` only fails when every shard is full, and `recv` only returns None
+/// when every shard was empty as it was scanned.
+pub struct ShardedMpmcQueue<T> {
+    shards: Box<[MpmcQueue<T>]>,
+    next_recv: AtomicUsize,
+}
+
+impl<T: Send> ShardedMpmcQueue<T> {
+    /// Creates a queue of `shards` shards sharing `total_capacity` slots.
+    ///
+    /// Each shard gets `total_capacity / shards` slots (at least 1), rounded
+    /// up to a power of 2 like `MpmcQueue::new`.
+    pub fn new(total_capacity: usize, shards: usize) -> Self {
+        assert!(shards > 0, "Shard count must be greater than 0");
+        assert!(total_capacity > 0, "Capacity must be greater than 0");
+
+        let per_shard = (total_capacity / shards).max(1);
+        Self {
+            shards: (0..shards).map(|_| MpmcQueue::new(per_shard)).collect(),
+            next_recv: AtomicUsize::new(0),
+        }
+    }
+
+    /// Attempts to send an item, starting at this thread's next shard and
+    /// moving on while shards are full.
+    ///
+    /// Returns the item back if every shard is full.
+    pub fn send(&self, item: T) -> Result<(), T> {
+        self.send_tagged(item).map(|_| ())
+    }
+
+    /// Like `send`, but returns the index of the shard the item went to.
+    ///
+    /// Shard indices run from 0 to `shard_count() - 1` and are the same
+    /// ones `recv_tagged` reports, so the two can be matched up.
+    pub fn send_tagged(&self, item: T) -> Result<usize, T> {
+        let start = NEXT_SHARD.with(|next| {
+            let start = next.get();
+            next.set(start.wrapping_add(1));
+            start
+        });
+
+        let mut item = item;
+        for offset in 0..self.shards.len() {
+            let index = self.shard_index(start.wrapping_add(offset));
+            match self.shards[index].send(item) {
+                Ok(()) => return Ok(index),
+                Err(rejected) => item = rejected,
+            }
+        }
+        Err(item)
+    }
+
+    /// Attempts to receive an item, scanning the shards from a rotating
+    /// starting point.
+    ///
+    /// Returns None if every shard was empty when scanned.
+    pub fn recv(&self) -> Option<T> {
+        self.recv_tagged().map(|(_, item)| item)
+    }
+
+    /// Like `recv`, but also returns the index of the shard the item came
+    /// from, as `(shard, item)`.
+    ///
+    /// The index is the one `send_tagged` reported for the item, in
+    /// `0..shard_count()`. A consumer can use it to keep draining the same
+    /// shard while it is warm in cache, or to route an acknowledgment back
+    /// to whoever feeds that shard. FIFO order holds among items with the
+    /// same index.
+    pub fn recv_tagged(&self) -> Option<(usize, T)> {
+        let start = self.next_recv.fetch_add(1, Ordering::Relaxed);
+        (0..self.shards.len()).find_map(|offset| {
+            let index = self.shard_index(start.wrapping_add(offset));
+            self.shards[index].recv().map(|item| (index, item))
+        })
+    }
+
+    /// Returns the number of shards.
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Returns the total capacity over all shards.
+    pub fn capacity(&self) -> usize {
+        self.shards.iter().map(MpmcQueue::capacity).sum()
+    }
+
+    /// Returns the approximate number of items over all shards.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn len(&self) -> usize {
+        self.shards.iter().map(MpmcQueue::len).sum()
+    }
+
+    /// Returns true if every shard is empty.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn is_empty(&self) -> bool {
+        self.shards.iter().all(MpmcQueue::is_empty)
+    }
+
+    fn shard_index(&self, position: usize) -> usize {
+        position % self.shards.len()
+    }
+}
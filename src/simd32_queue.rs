@@ -0,0 +1,446 @@
+use std::cell::UnsafeCell;
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+use std::simd::cmp::SimdPartialEq;
+use std::simd::ptr::SimdMutPtr;
+use std::simd::{u32x8, u64x8, usizex8, Mask, Simd};
+use std::sync::atomic::Ordering;
+
+use crate::seq::{advance, depth, free_seq, next_seq};
+use crate::sync::AtomicUsize;
+use crate::wait_strategy::{Backoff, WaitStrategy};
+use crate::{ConsumerPos, ProducerPos};
+
+/// Number of 32-bit lanes moved per SIMD batch
+const LANES: usize = 8;
+
+/// SIMD-optimized MPMC queue for 32-bit data types
+///
+/// The 32-bit counterpart of `SimdMpmcQueue`: batches claim and move eight
+/// elements per `u32x8` vector instead of four per `u64x4`, doubling the
+/// elements per vector operation for `u32`/`i32`/`f32` payloads.
+///
+/// Supported types: u32, i32, f32, and any type implementing `Simd32Bit`
+pub struct Simd32MpmcQueue<T> {
+    buffer: Box<[Simd32Slot]>,
+    capacity: usize,
+    mask: usize,
+    producer_pos: ProducerPos,
+    consumer_pos: ConsumerPos,
+    _marker: PhantomData<T>,
+}
+
+// Items are stored as their `to_u32` bits, see `SimdMpmcQueue`
+#[repr(align(64))]
+#[cfg_attr(all(target_arch = "aarch64", target_vendor = "apple"), repr(align(128)))]
+#[cfg_attr(target_arch = "s390x", repr(align(256)))]
+struct Simd32Slot {
+    sequence: AtomicUsize,
+    data: UnsafeCell<MaybeUninit<u32>>,
+}
+
+impl Simd32Slot {
+    fn new(seq: usize) -> Self {
+        Self {
+            sequence: AtomicUsize::new(seq),
+            data: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+}
+
+/// Trait to enable SIMD operations for 32-bit types
+pub trait Simd32Bit: Copy + Send + Sync + 'static {
+    /// Convert to u32 for SIMD processing
+    fn to_u32(self) -> u32;
+    /// Convert from u32 after SIMD processing
+    fn from_u32(val: u32) -> Self;
+}
+
+impl Simd32Bit for u32 {
+    fn to_u32(self) -> u32 { self }
+    fn from_u32(val: u32) -> Self { val }
+}
+
+impl Simd32Bit for i32 {
+    fn to_u32(self) -> u32 { self as u32 }
+    fn from_u32(val: u32) -> Self { val as i32 }
+}
+
+impl Simd32Bit for f32 {
+    fn to_u32(self) -> u32 { self.to_bits() }
+    fn from_u32(val: u32) -> Self { f32::from_bits(val) }
+}
+
+// The 32-bit counterparts of the `Simd64Bit` impls in `simd_queue`
+#[cfg(target_pointer_width = "32")]
+impl Simd32Bit for usize {
+    fn to_u32(self) -> u32 { self as u32 }
+    fn from_u32(val: u32) -> Self { val as usize }
+}
+
+#[cfg(target_pointer_width = "32")]
+impl Simd32Bit for isize {
+    fn to_u32(self) -> u32 { self as u32 }
+    fn from_u32(val: u32) -> Self { val as isize }
+}
+
+/// SIMD-optimized operations for 32-bit data types
+impl<T: Simd32Bit> Simd32MpmcQueue<T> {
+    /// Creates a new SIMD-optimized MPMC queue for 32-bit elements
+    ///
+    /// The capacity is rounded up to a power of 2 of at least two batches.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "Capacity must be greater than 0");
+
+        let capacity = std::cmp::max(capacity.next_power_of_two(), LANES * 2);
+        let buffer: Vec<_> = (0..capacity).map(Simd32Slot::new).collect();
+
+        Self {
+            buffer: buffer.into_boxed_slice(),
+            capacity,
+            mask: capacity - 1,
+            producer_pos: ProducerPos {
+                head: AtomicUsize::new(0),
+            },
+            consumer_pos: ConsumerPos {
+                tail: AtomicUsize::new(0),
+            },
+            _marker: PhantomData,
+        }
+    }
+
+    /// Send items - uses 8-wide SIMD batches, then single sends for the
+    /// 1-7 item tail
+    ///
+    /// Returns `Err(n)` with the number of leading items sent if the queue
+    /// fills up, like `SimdMpmcQueue::send`.
+    pub fn send(&self, items: &[T]) -> Result<usize, usize> {
+        let mut sent_count = 0;
+        let mut remaining_items = items;
+
+        while !remaining_items.is_empty() {
+            if remaining_items.len() >= LANES {
+                let head = self.producer_pos.head.load(Ordering::Relaxed);
+                if self.try_claim_batch_producer(head, LANES) {
+                    unsafe {
+                        self.store_batch_simd(head, &remaining_items[..LANES]);
+                    }
+                    sent_count += LANES;
+                    remaining_items = &remaining_items[LANES..];
+                    continue;
+                }
+            }
+
+            // Short tail, or the batch could not be claimed: send one item
+            match self.send_single_internal(remaining_items[0]) {
+                Ok(()) => {
+                    sent_count += 1;
+                    remaining_items = &remaining_items[1..];
+                }
+                Err(_) => {
+                    // Queue full, report how many made it in
+                    return Err(sent_count);
+                }
+            }
+        }
+
+        Ok(sent_count)
+    }
+
+    /// Receive items - uses 8-wide SIMD batches, then single receives for
+    /// the 1-7 slot tail
+    ///
+    /// Like `SimdMpmcQueue::recv`, a short count means the queue ran out of
+    /// ready items: batches that wrap around the end of the ring are
+    /// gathered like any other.
+    pub fn recv(&self, buffer: &mut [T]) -> usize {
+        let mut received_count = 0;
+
+        while received_count < buffer.len() {
+            let remaining_buffer = &mut buffer[received_count..];
+            if remaining_buffer.len() >= LANES {
+                let tail = self.consumer_pos.tail.load(Ordering::Relaxed);
+                if self.try_claim_batch_consumer(tail, LANES) {
+                    unsafe {
+                        self.load_batch_simd(tail, &mut remaining_buffer[..LANES]);
+                    }
+                    received_count += LANES;
+                    continue;
+                }
+            }
+
+            // Short tail, or the batch could not be claimed: take one item
+            match self.recv_single_internal() {
+                Some(item) => {
+                    remaining_buffer[0] = item;
+                    received_count += 1;
+                }
+                None => break, // No more data available
+            }
+        }
+
+        received_count
+    }
+
+    /// Send a single SIMD batch of up to 8 items, all or nothing
+    ///
+    /// See `SimdMpmcQueue::send_batch`.
+    pub fn send_batch(&self, items: &[T]) -> Result<usize, usize> {
+        let batch = &items[..std::cmp::min(items.len(), LANES)];
+        if batch.is_empty() {
+            return Ok(0);
+        }
+
+        let head = self.producer_pos.head.load(Ordering::Relaxed);
+        if self.try_claim_batch_producer(head, batch.len()) {
+            unsafe {
+                self.store_batch_simd(head, batch);
+            }
+            Ok(batch.len())
+        } else {
+            Err(batch.len())
+        }
+    }
+
+    /// Receive a single SIMD batch of up to 8 items, all or nothing
+    ///
+    /// See `SimdMpmcQueue::recv_batch`.
+    pub fn recv_batch(&self, buffer: &mut [T]) -> usize {
+        let batch_size = std::cmp::min(buffer.len(), LANES);
+        if batch_size == 0 {
+            return 0;
+        }
+
+        let tail = self.consumer_pos.tail.load(Ordering::Relaxed);
+        if self.try_claim_batch_consumer(tail, batch_size) {
+            unsafe {
+                self.load_batch_simd(tail, &mut buffer[..batch_size]);
+            }
+            batch_size
+        } else {
+            0
+        }
+    }
+
+    /// Send single item
+    pub fn send_one(&self, item: T) -> Result<(), T> {
+        self.send_single_internal(item)
+    }
+
+    /// Receive single item
+    pub fn recv_one(&self) -> Option<T> {
+        self.recv_single_internal()
+    }
+
+    /// Returns the capacity of the queue
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns true if the queue is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns true if the queue is full
+    pub fn is_full(&self) -> bool {
+        self.len() >= self.capacity
+    }
+
+    /// Returns the approximate number of items in the queue
+    pub fn len(&self) -> usize {
+        // Tail first, so a consumer racing ahead cannot make the depth
+        // wrap; clamped since producers may move on in between too
+        let tail = self.consumer_pos.tail.load(Ordering::Acquire);
+        let head = self.producer_pos.head.load(Ordering::Acquire);
+        depth(head, tail).min(self.capacity)
+    }
+
+    /// Try to claim a batch of producer slots using SIMD sequence checking
+    fn try_claim_batch_producer(&self, head: usize, batch_size: usize) -> bool {
+        self.batch_ready(head, head, batch_size)
+            && self.producer_pos.head.compare_exchange_weak(
+                head,
+                advance(head, batch_size),
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ).is_ok()
+    }
+
+    /// Try to claim a batch of consumer slots using SIMD sequence checking
+    fn try_claim_batch_consumer(&self, tail: usize, batch_size: usize) -> bool {
+        self.batch_ready(tail, next_seq(tail), batch_size)
+            && self.consumer_pos.tail.compare_exchange_weak(
+                tail,
+                advance(tail, batch_size),
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ).is_ok()
+    }
+
+    /// True if the first `batch_size` slots from `pos` carry the sequences
+    /// `start_seq, start_seq + 1, ...`, compared in one 8-wide `simd_eq`
+    fn batch_ready(&self, pos: usize, start_seq: usize, batch_size: usize) -> bool {
+        let mut sequences = [0u64; LANES];
+        for (i, sequence) in sequences.iter_mut().enumerate().take(batch_size) {
+            let slot = &self.buffer[advance(pos, i) & self.mask];
+            *sequence = slot.sequence.load(Ordering::Acquire) as u64;
+        }
+        let offsets = u64x8::from_array(std::array::from_fn(|i| i as u64));
+        let expected = u64x8::splat(start_seq as u64) + offsets;
+
+        let lanes = (1u64 << batch_size) - 1;
+        u64x8::from_array(sequences).simd_eq(expected).to_bitmask() & lanes == lanes
+    }
+
+    /// Pointers to the payloads of the eight slots starting at `pos`
+    fn payload_ptrs(&self, pos: usize) -> Simd<*mut u32, LANES> {
+        let first = pos & self.mask;
+        if first + LANES <= self.capacity {
+            // Contiguous run: one base pointer stepped by the slot size. It
+            // is derived from the whole buffer rather than from one slot's
+            // cell, so its provenance covers every slot it is stepped to
+            let slot = self.buffer.as_ptr().wrapping_add(first);
+            let base = unsafe { UnsafeCell::raw_get(&raw const (*slot).data) }.cast::<u32>();
+            let stride = std::mem::size_of::<Simd32Slot>() / std::mem::size_of::<u32>();
+            Simd::splat(base).wrapping_add(usizex8::from_array(std::array::from_fn(|i| i * stride)))
+        } else {
+            // The run wraps around the end of the ring
+            Simd::from_array(std::array::from_fn(|i| {
+                self.buffer[advance(pos, i) & self.mask].data.get().cast::<u32>()
+            }))
+        }
+    }
+
+    /// Lane mask enabling the first `batch_size` lanes
+    fn batch_lanes(batch_size: usize) -> Mask<isize, LANES> {
+        Mask::from_array(std::array::from_fn(|i| i < batch_size))
+    }
+
+    /// Store batch data with a single vector scatter, then publish each slot
+    unsafe fn store_batch_simd(&self, head: usize, items: &[T]) {
+        let batch_size = items.len().min(LANES);
+        let mut lanes = [0u32; LANES];
+        for (lane, item) in lanes.iter_mut().zip(items) {
+            *lane = item.to_u32();
+        }
+
+        // The claimed slots are ours until their sequences are published
+        unsafe {
+            u32x8::from_array(lanes)
+                .scatter_select_ptr(self.payload_ptrs(head), Self::batch_lanes(batch_size));
+        }
+
+        for i in 0..batch_size {
+            let pos = advance(head, i);
+            self.buffer[pos & self.mask]
+                .sequence
+                .store(next_seq(pos), Ordering::Release);
+        }
+    }
+
+    /// Load batch data with a single vector gather, then release each slot
+    unsafe fn load_batch_simd(&self, tail: usize, buffer: &mut [T]) {
+        let batch_size = buffer.len().min(LANES);
+
+        let values = unsafe {
+            u32x8::gather_select_ptr(
+                self.payload_ptrs(tail).cast_const(),
+                Self::batch_lanes(batch_size),
+                u32x8::splat(0),
+            )
+        };
+        for (dst, value) in buffer.iter_mut().zip(values.to_array()) {
+            *dst = T::from_u32(value);
+        }
+
+        for i in 0..batch_size {
+            let pos = advance(tail, i);
+            self.buffer[pos & self.mask]
+                .sequence
+                .store(free_seq(pos, self.capacity), Ordering::Release);
+        }
+    }
+
+    /// Internal single-element send implementation
+    fn send_single_internal(&self, item: T) -> Result<(), T> {
+        let backoff = Backoff::default();
+        let mut attempt = 0u32;
+        loop {
+            let head = self.producer_pos.head.load(Ordering::Relaxed);
+            let slot = &self.buffer[head & self.mask];
+
+            match slot.sequence.load(Ordering::Acquire).cmp(&head) {
+                std::cmp::Ordering::Equal => {
+                    if self.producer_pos.head.compare_exchange_weak(
+                        head,
+                        next_seq(head),
+                        Ordering::Relaxed,
+                        Ordering::Relaxed,
+                    ).is_ok() {
+                        unsafe {
+                            (*slot.data.get()).write(item.to_u32());
+                        }
+                        slot.sequence.store(next_seq(head), Ordering::Release);
+                        return Ok(());
+                    }
+                }
+                std::cmp::Ordering::Less => {
+                    let tail = self.consumer_pos.tail.load(Ordering::Acquire);
+                    if depth(head, tail) >= self.capacity {
+                        return Err(item);
+                    }
+                }
+                std::cmp::Ordering::Greater => {
+                    // Another producer claimed `head`, wait until we see it move
+                    while self.producer_pos.head.load(Ordering::Acquire) == head {
+                        backoff.wait(attempt);
+                        attempt = attempt.saturating_add(1);
+                    }
+                    continue;
+                }
+            }
+            backoff.wait(attempt);
+            attempt = attempt.saturating_add(1);
+        }
+    }
+
+    /// Internal single-element receive implementation
+    fn recv_single_internal(&self) -> Option<T> {
+        let backoff = Backoff::default();
+        let mut attempt = 0u32;
+        loop {
+            let tail = self.consumer_pos.tail.load(Ordering::Relaxed);
+            let slot = &self.buffer[tail & self.mask];
+
+            match slot.sequence.load(Ordering::Acquire).cmp(&next_seq(tail)) {
+                std::cmp::Ordering::Equal => {
+                    if self.consumer_pos.tail.compare_exchange_weak(
+                        tail,
+                        next_seq(tail),
+                        Ordering::Relaxed,
+                        Ordering::Relaxed,
+                    ).is_ok() {
+                        let item = T::from_u32(unsafe { (*slot.data.get()).assume_init_read() });
+                        slot.sequence.store(free_seq(tail, self.capacity), Ordering::Release);
+                        return Some(item);
+                    }
+                }
+                std::cmp::Ordering::Less => return None,
+                std::cmp::Ordering::Greater => {
+                    // Another consumer claimed `tail`, wait until we see it move
+                    while self.consumer_pos.tail.load(Ordering::Acquire) == tail {
+                        backoff.wait(attempt);
+                        attempt = attempt.saturating_add(1);
+                    }
+                    continue;
+                }
+            }
+            backoff.wait(attempt);
+            attempt = attempt.saturating_add(1);
+        }
+    }
+}
+
+unsafe impl<T: Simd32Bit> Send for Simd32MpmcQueue<T> {}
+unsafe impl<T: Simd32Bit> Sync for Simd32MpmcQueue<T> {}
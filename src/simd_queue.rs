@@ -1,48 +1,169 @@
+#[cfg(feature = "metrics")]
+use std::sync::atomic::AtomicU64;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::cell::UnsafeCell;
-use std::mem::MaybeUninit;
-use std::simd::{u64x4};
+use std::marker::PhantomData;
+use std::mem::{ManuallyDrop, MaybeUninit};
+use std::simd::ptr::SimdMutPtr;
+use std::simd::{Mask, Simd};
 use std::simd::cmp::SimdPartialEq;
 
+use crate::aligned::{Aligned, Alignment};
+use crate::seq::{advance, depth, free_seq, next_seq};
+use crate::wait_strategy::{Backoff, WaitStrategy};
+
 /// SIMD-optimized MPMC queue for 64-bit data types
 /// 
-/// This version uses SIMD instructions to process multiple elements simultaneously,
-/// providing significant performance improvements for 64-bit data.
+/// `send` and `recv` move `LANES` elements per `Simd<u64, LANES>` batch:
+/// one CAS claims the whole batch and one `simd_eq` checks its sequence
+/// numbers. The payloads themselves sit one per cache-line slot, so they
+/// are copied with a strided gather/scatter rather than a contiguous
+/// vector load/store, and x86 targets lower that to one move per lane.
+/// The gain over `send_one`/`recv_one` comes from the shared claim, not
+/// from vectorizing the copy.
+/// The default of 4 (`u64x4`) suits AVX2 and NEON; pick
+/// `SimdMpmcQueue<T, 8>` on CPUs with AVX-512. Any lane count `std::simd`
+/// supports works, and the capacity is rounded up to hold two batches.
 /// 
-/// Supported types: u64, i64, f64, usize, isize, and any 64-bit type that can be safely transmuted
+/// Supported types: u64, i64, f64, usize, isize, and any 64-bit type that
+/// implements `Simd64Bit`. Other `Copy` types can opt in through
+/// `SimdElement` and keep the batch API without the vectorization.
 #[repr(align(64))]
-pub struct SimdMpmcQueue<T> {
+#[cfg_attr(all(target_arch = "aarch64", target_vendor = "apple"), repr(align(128)))]
+#[cfg_attr(target_arch = "s390x", repr(align(256)))]
+pub struct SimdMpmcQueue<T, const LANES: usize = 4> {
     buffer: Box<[SimdSlot<T>]>,
     capacity: usize,
     mask: usize,
     producer_pos: SimdProducerPos,
     consumer_pos: SimdConsumerPos,
+    // Largest len() seen after a send, see `high_water_mark`
+    #[cfg(feature = "metrics")]
+    high_water_mark: AtomicUsize,
+    // Batches moved slot by slot because they wrapped, see `wrapped_batches`
+    #[cfg(feature = "metrics")]
+    wrapped_batches: AtomicU64,
+    _marker: PhantomData<T>,
 }
 
+// Vectorized items are stored as their `to_lane` bits, so every payload is
+// a plain u64 that SIMD gathers and scatters can move regardless of `T`;
+// other items are stored as they are
 #[repr(align(64))]
+#[cfg_attr(all(target_arch = "aarch64", target_vendor = "apple"), repr(align(128)))]
+#[cfg_attr(target_arch = "s390x", repr(align(256)))]
 struct SimdSlot<T> {
     sequence: AtomicUsize,
-    data: UnsafeCell<MaybeUninit<T>>,
+    data: UnsafeCell<MaybeUninit<Payload<T>>>,
+}
+
+// Both forms start at the slot's payload address, which `payload_ptrs`
+// relies on
+#[repr(C)]
+union Payload<T> {
+    lane: u64,
+    item: ManuallyDrop<T>,
 }
 
 #[repr(align(64))]
+#[cfg_attr(all(target_arch = "aarch64", target_vendor = "apple"), repr(align(128)))]
+#[cfg_attr(target_arch = "s390x", repr(align(256)))]
 struct SimdProducerPos {
     head: AtomicUsize,
 }
 
 #[repr(align(64))]
+#[cfg_attr(all(target_arch = "aarch64", target_vendor = "apple"), repr(align(128)))]
+#[cfg_attr(target_arch = "s390x", repr(align(256)))]
 struct SimdConsumerPos {
     tail: AtomicUsize,
 }
 
-impl<T> SimdSlot<T> {
+impl<T: SimdElement> SimdSlot<T> {
     fn new(seq: usize) -> Self {
         Self {
             sequence: AtomicUsize::new(seq),
             data: UnsafeCell::new(MaybeUninit::uninit()),
         }
     }
+    
+    // Writes the item in the form `T` is stored in. The caller must own
+    // the slot
+    unsafe fn write(&self, item: T) {
+        let payload = if T::VECTORIZED {
+            Payload { lane: item.to_lane() }
+        } else {
+            Payload { item: ManuallyDrop::new(item) }
+        };
+        unsafe { (*self.data.get()).write(payload) };
+    }
+    
+    // Reads the item written by `write` or a scatter. The caller must own
+    // the slot and it must hold an item
+    unsafe fn read(&self) -> T {
+        let payload = unsafe { (*self.data.get()).assume_init_read() };
+        if T::VECTORIZED {
+            T::from_lane(unsafe { payload.lane })
+        } else {
+            ManuallyDrop::into_inner(unsafe { payload.item })
+        }
+    }
+}
+
+/// Element types a `SimdMpmcQueue` can carry
+/// 
+/// Every `Simd64Bit` type is one, and its batches move as `u64` lanes with
+/// SIMD gathers and scatters. Any other `Copy` type opts in with an empty
+/// impl and takes the scalar path: the same batch API, with a batch still
+/// claimed by one CAS, but its items copied slot by slot.
+/// 
+/// ```
+/// #![feature(portable_simd)]
+/// use mpmc_std::simd_queue::{SimdElement, SimdMpmcQueue};
+/// 
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// struct Pixel {
+///     x: u16,
+///     y: u16,
+///     rgb: [u8; 3],
+/// }
+/// 
+/// impl SimdElement for Pixel {}
+/// 
+/// let queue = SimdMpmcQueue::<Pixel>::new(16);
+/// let pixel = Pixel { x: 1, y: 2, rgb: [255, 0, 0] };
+/// assert_eq!(queue.send(&[pixel; 3]), Ok(3));
+/// let mut out = [Pixel { x: 0, y: 0, rgb: [0; 3] }; 3];
+/// assert_eq!(queue.recv(&mut out), 3);
+/// assert_eq!(out, [pixel; 3]);
+/// ```
+pub trait SimdElement: Copy + Send + Sync + 'static {
+    /// True if items move as `u64` lanes through `to_lane` and
+    /// `from_lane`. Leave it false for the scalar path.
+    const VECTORIZED: bool = false;
+    
+    /// Converts the item to a SIMD lane; only called when `VECTORIZED`
+    fn to_lane(self) -> u64 {
+        unreachable!("only vectorized elements move as lanes")
+    }
+    
+    /// Converts a SIMD lane back to an item; only called when `VECTORIZED`
+    fn from_lane(_lane: u64) -> Self {
+        unreachable!("only vectorized elements move as lanes")
+    }
+}
+
+impl<T: Simd64Bit> SimdElement for T {
+    const VECTORIZED: bool = true;
+    
+    fn to_lane(self) -> u64 {
+        self.to_u64()
+    }
+    
+    fn from_lane(lane: u64) -> Self {
+        T::from_u64(lane)
+    }
 }
 
 /// Trait to enable SIMD operations for 64-bit types
@@ -68,33 +189,38 @@ impl Simd64Bit for f64 {
     fn from_u64(val: u64) -> Self { f64::from_bits(val) }
 }
 
+// Pointer-sized integers only fit the 64-bit queue on 64-bit targets; on
+// 32-bit ones they implement `Simd32Bit` instead
+#[cfg(target_pointer_width = "64")]
 impl Simd64Bit for usize {
-    fn to_u64(self) -> u64 { 
-        assert_eq!(std::mem::size_of::<usize>(), 8, "usize must be 64-bit");
-        self as u64 
-    }
+    fn to_u64(self) -> u64 { self as u64 }
     fn from_u64(val: u64) -> Self { val as usize }
 }
 
+#[cfg(target_pointer_width = "64")]
 impl Simd64Bit for isize {
-    fn to_u64(self) -> u64 { 
-        assert_eq!(std::mem::size_of::<isize>(), 8, "isize must be 64-bit");
-        self as u64 
-    }
+    fn to_u64(self) -> u64 { self as u64 }
     fn from_u64(val: u64) -> Self { val as isize }
 }
 
+// Lets aligned elements use the batch API; the lane is still copied on its
+// own, so the over-alignment only adds padding (see `crate::aligned`)
+impl<T: Simd64Bit, A: Alignment> Simd64Bit for Aligned<T, A> {
+    fn to_u64(self) -> u64 { self.into_inner().to_u64() }
+    fn from_u64(val: u64) -> Self { Aligned::new(T::from_u64(val)) }
+}
+
 /// SIMD-optimized operations for 64-bit data types
-impl<T: Simd64Bit> SimdMpmcQueue<T> {
+impl<T: SimdElement, const LANES: usize> SimdMpmcQueue<T, LANES> {
     /// Creates a new SIMD-optimized MPMC queue for 64-bit elements
     pub fn new(capacity: usize) -> Self {
         assert!(capacity > 0, "Capacity must be greater than 0");
+        assert!(LANES.is_power_of_two(), "LANES must be a power of 2");
         
-        // Ensure capacity is power of 2 and divisible by SIMD width
-        let simd_batch_size = 4; // u64x4 SIMD width
+        // Ensure capacity is power of 2 and holds two full batches
         let capacity = std::cmp::max(
             capacity.next_power_of_two(),
-            simd_batch_size * 2
+            LANES * 2
         );
         let mask = capacity - 1;
         
@@ -113,56 +239,49 @@ impl<T: Simd64Bit> SimdMpmcQueue<T> {
             consumer_pos: SimdConsumerPos {
                 tail: AtomicUsize::new(0),
             },
+            #[cfg(feature = "metrics")]
+            high_water_mark: AtomicUsize::new(0),
+            #[cfg(feature = "metrics")]
+            wrapped_batches: AtomicU64::new(0),
+            _marker: PhantomData,
         }
     }
     
     /// Send items - automatically uses SIMD when beneficial
-    pub fn send(&self, items: &[T]) -> Result<usize, Vec<T>> {
-        if items.is_empty() {
-            return Ok(0);
-        }
-        
+    /// 
+    /// Items are enqueued in order. If the queue fills up part way, returns
+    /// `Err(n)` with the number of leading items that were sent, so the
+    /// caller can retry with `&items[n..]` without the queue allocating.
+    /// 
+    /// Each claim is sized to the room left in the queue: `LANES` slots
+    /// while the queue has that many free, then halving down to 2, so a
+    /// nearly full queue is not hit with batch claims that cannot succeed.
+    /// Single slots take the scalar path.
+    pub fn send(&self, items: &[T]) -> Result<usize, usize> {
         let mut sent_count = 0;
         let mut remaining_items = items;
         
-        // Try SIMD batch operations first for groups of 4
-        while remaining_items.len() >= 4 {
-            let batch = &remaining_items[..4];
-            
-            // Check if we can claim 4 slots using SIMD
+        while !remaining_items.is_empty() {
             let head = self.producer_pos.head.load(Ordering::Relaxed);
-            if self.try_claim_batch_producer(head, 4) {
-                // Use SIMD to store 4 items
+            let width = Self::adaptive_width(remaining_items.len(), self.writable(head));
+            if width > 1 && self.try_claim_batch_producer(head, width) {
                 unsafe {
-                    self.store_batch_simd(head, batch);
-                }
-                sent_count += 4;
-                remaining_items = &remaining_items[4..];
-            } else {
-                // SIMD batch failed, try single item
-                match self.send_single_internal(remaining_items[0]) {
-                    Ok(()) => {
-                        sent_count += 1;
-                        remaining_items = &remaining_items[1..];
-                    }
-                    Err(_) => {
-                        // Queue full, return what we couldn't send
-                        return Err(remaining_items.to_vec());
-                    }
+                    self.store_batch_simd(head, &remaining_items[..width]);
                 }
+                sent_count += width;
+                remaining_items = &remaining_items[width..];
+                continue;
             }
-        }
-        
-        // Handle remaining items (1-3 items) individually
-        while !remaining_items.is_empty() {
+            
+            // Too little room for a batch or the CAS lost, try single item
             match self.send_single_internal(remaining_items[0]) {
                 Ok(()) => {
                     sent_count += 1;
                     remaining_items = &remaining_items[1..];
                 }
                 Err(_) => {
-                    // Queue full, return what we couldn't send
-                    return Err(remaining_items.to_vec());
+                    // Queue full, report how many made it in
+                    return Err(sent_count);
                 }
             }
         }
@@ -172,6 +291,11 @@ impl<T: Simd64Bit> SimdMpmcQueue<T> {
     
     
     /// Receive items - automatically uses SIMD when beneficial  
+    /// 
+    /// Fills `buffer` with every item ready at the tail, up to its length,
+    /// and returns how many were received. A run that wraps around the end
+    /// of the ring is still claimed as one batch, so a short count always
+    /// means the queue ran out of ready items, never that the wrap was hit.
     pub fn recv(&self, buffer: &mut [T]) -> usize {
         if buffer.is_empty() {
             return 0;
@@ -180,36 +304,24 @@ impl<T: Simd64Bit> SimdMpmcQueue<T> {
         let mut received_count = 0;
         let mut remaining_buffer = buffer;
         
-        // Try SIMD batch operations first for groups of 4
-        while remaining_buffer.len() >= 4 {
+        while !remaining_buffer.is_empty() {
             let tail = self.consumer_pos.tail.load(Ordering::Relaxed);
             
-            // Check if we can claim 4 slots using SIMD
-            if self.try_claim_batch_consumer(tail, 4) {
-                // Use SIMD to load 4 items
+            // Claim however many slots are ready, up to one batch, so a
+            // partially filled run still moves in one SIMD operation; with
+            // at most one item queued, the scalar path is cheaper
+            let want = remaining_buffer.len().min(LANES).min(self.available(tail));
+            let claimed = if want > 1 { self.try_claim_ready_run(tail, want) } else { 0 };
+            if claimed > 0 {
                 unsafe {
-                    self.load_batch_simd(tail, &mut remaining_buffer[..4]);
-                }
-                received_count += 4;
-                remaining_buffer = &mut remaining_buffer[4..];
-            } else {
-                // SIMD batch failed, try single item
-                match self.recv_single_internal() {
-                    Some(item) => {
-                        remaining_buffer[0] = item;
-                        received_count += 1;
-                        remaining_buffer = &mut remaining_buffer[1..];
-                    }
-                    None => {
-                        // No more data available
-                        return received_count;
-                    }
+                    self.load_batch_simd(tail, &mut remaining_buffer[..claimed]);
                 }
+                received_count += claimed;
+                remaining_buffer = &mut remaining_buffer[claimed..];
+                continue;
             }
-        }
-        
-        // Handle remaining buffer space (1-3 slots) individually
-        while !remaining_buffer.is_empty() {
+            
+            // Nothing ready at the tail or the CAS lost, try single item
             match self.recv_single_internal() {
                 Some(item) => {
                     remaining_buffer[0] = item;
@@ -227,125 +339,215 @@ impl<T: Simd64Bit> SimdMpmcQueue<T> {
     }
     
     
+    /// Width of the next batch claim for `wanted` items with `room` slots
+    /// free or ready: the largest power of 2 within both, capped at `LANES`
+    fn adaptive_width(wanted: usize, room: usize) -> usize {
+        match wanted.min(room).min(LANES) {
+            0 => 0,
+            n => 1 << n.ilog2(),
+        }
+    }
+    
+    /// Approximate number of free slots from `head`, for sizing a claim
+    fn writable(&self, head: usize) -> usize {
+        let tail = self.consumer_pos.tail.load(Ordering::Acquire);
+        self.capacity - depth(head, tail).min(self.capacity)
+    }
+    
+    /// Approximate number of items from `tail`, for sizing a claim
+    fn available(&self, tail: usize) -> usize {
+        let head = self.producer_pos.head.load(Ordering::Acquire);
+        depth(head, tail).min(self.capacity)
+    }
+    
     /// Try to claim a batch of producer slots using SIMD sequence checking
     fn try_claim_batch_producer(&self, head: usize, batch_size: usize) -> bool {
-        // Load sequence numbers for the batch using SIMD
-        let sequences = unsafe { self.load_sequences_simd(head, batch_size) };
-        let expected_sequences = self.generate_expected_sequences_simd(head, batch_size);
-        
-        // Check if all sequences match expected values
-        let mask = sequences.simd_eq(expected_sequences);
-        
-        if mask.all() {
-            // All slots are available, try to claim them atomically
-            self.producer_pos.head.compare_exchange_weak(
+        // All slots are available, try to claim them atomically
+        self.batch_ready(head, head, batch_size)
+            && self.producer_pos.head.compare_exchange_weak(
                 head,
-                head.wrapping_add(batch_size),
+                advance(head, batch_size),
                 Ordering::Relaxed,
                 Ordering::Relaxed,
             ).is_ok()
-        } else {
-            false
-        }
     }
     
     /// Try to claim a batch of consumer slots using SIMD sequence checking
     fn try_claim_batch_consumer(&self, tail: usize, batch_size: usize) -> bool {
-        // Load sequence numbers for the batch using SIMD
-        let sequences = unsafe { self.load_sequences_simd(tail, batch_size) };
-        let expected_sequences = self.generate_expected_sequences_simd(
-            tail.wrapping_add(1), batch_size
-        );
-        
-        // Check if all sequences match expected values
-        let mask = sequences.simd_eq(expected_sequences);
-        
-        if mask.all() {
-            // All slots have data, try to claim them atomically
-            self.consumer_pos.tail.compare_exchange_weak(
+        // All slots have data, try to claim them atomically
+        self.batch_ready(tail, next_seq(tail), batch_size)
+            && self.consumer_pos.tail.compare_exchange_weak(
+                tail,
+                advance(tail, batch_size),
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ).is_ok()
+    }
+    
+    /// Claim the longest ready prefix of up to `max` slots from `tail` with
+    /// a single CAS, returning how many were claimed (0 if none or if the
+    /// CAS lost a race)
+    fn try_claim_ready_run(&self, tail: usize, max: usize) -> usize {
+        let ready = self.ready_prefix(tail, next_seq(tail), max);
+        if ready > 0
+            && self.consumer_pos.tail.compare_exchange_weak(
                 tail,
-                tail.wrapping_add(batch_size),
+                advance(tail, ready),
                 Ordering::Relaxed,
                 Ordering::Relaxed,
             ).is_ok()
+        {
+            ready
         } else {
-            false
+            0
         }
     }
     
+    /// True if the first `batch_size` slots from `pos` carry the sequences
+    /// `start_seq, start_seq + 1, ...`, checked with a `LANES`-wide `simd_eq`
+    fn batch_ready(&self, pos: usize, start_seq: usize, batch_size: usize) -> bool {
+        self.ready_prefix(pos, start_seq, batch_size) == batch_size
+    }
+    
+    /// Number of leading slots, up to `batch_size`, that carry the expected
+    /// sequences (the mask's leading ones, in lane order)
+    fn ready_prefix(&self, pos: usize, start_seq: usize, batch_size: usize) -> usize {
+        let sequences = unsafe { self.load_sequences_simd(pos, batch_size) };
+        let expected = Self::generate_expected_sequences_simd(start_seq);
+        Self::leading_matches(sequences.simd_eq(expected).to_bitmask(), batch_size)
+    }
+    
+    /// Length of the run of set lanes starting at lane 0, capped at
+    /// `batch_size` (lanes past the batch are ignored)
+    fn leading_matches(bitmask: u64, batch_size: usize) -> usize {
+        std::cmp::min((!bitmask).trailing_zeros() as usize, batch_size)
+    }
+    
     /// Load sequence numbers using SIMD instructions
-    unsafe fn load_sequences_simd(&self, start_pos: usize, batch_size: usize) -> u64x4 {
-        let mut sequences = [0u64; 4];
-        for i in 0..std::cmp::min(batch_size, 4) {
-            let slot_idx = (start_pos.wrapping_add(i)) & self.mask;
-            sequences[i] = self.buffer[slot_idx].sequence.load(Ordering::Acquire) as u64;
+    unsafe fn load_sequences_simd(&self, start_pos: usize, batch_size: usize) -> Simd<u64, LANES> {
+        let mut sequences = [0u64; LANES];
+        for (i, sequence) in sequences.iter_mut().enumerate().take(batch_size) {
+            let slot_idx = advance(start_pos, i) & self.mask;
+            *sequence = self.buffer[slot_idx].sequence.load(Ordering::Acquire) as u64;
         }
-        u64x4::from_array(sequences)
+        Simd::from_array(sequences)
     }
     
     /// Generate expected sequence numbers using SIMD
-    fn generate_expected_sequences_simd(&self, start_seq: usize, _batch_size: usize) -> u64x4 {
-        let base_seq = start_seq as u64;
-        let offsets = u64x4::from_array([0, 1, 2, 3]);
-        u64x4::splat(base_seq) + offsets
+    fn generate_expected_sequences_simd(start_seq: usize) -> Simd<u64, LANES> {
+        let offsets = Simd::from_array(std::array::from_fn(|i| i as u64));
+        Simd::splat(start_seq as u64) + offsets
+    }
+    
+    /// True if the `batch_size` slots from `pos` stay within the buffer
+    /// instead of wrapping around its end
+    fn is_contiguous(&self, pos: usize, batch_size: usize) -> bool {
+        (pos & self.mask) + batch_size <= self.capacity
+    }
+    
+    /// Pointers to the payloads of the `LANES` slots starting at `pos`: one
+    /// base pointer stepped by the slot size. It is derived from the whole
+    /// buffer rather than from one slot's cell, so its provenance covers
+    /// every slot it is stepped to. Lanes past a run that ends at the end
+    /// of the buffer point beyond it, so they must be masked off.
+    fn payload_ptrs(&self, pos: usize) -> Simd<*mut u64, LANES> {
+        let slot = self.buffer.as_ptr().wrapping_add(pos & self.mask);
+        let base = unsafe { UnsafeCell::raw_get(&raw const (*slot).data) }.cast::<u64>();
+        let stride = std::mem::size_of::<SimdSlot<T>>() / std::mem::size_of::<u64>();
+        Simd::splat(base).wrapping_add(Simd::from_array(std::array::from_fn(|i| i * stride)))
+    }
+    
+    /// Lane mask enabling the first `batch_size` lanes
+    fn batch_lanes(batch_size: usize) -> Mask<isize, LANES> {
+        Mask::from_array(std::array::from_fn(|i| i < batch_size))
     }
     
     /// Store batch data using SIMD operations
+    /// 
+    /// The payloads are written with a single `LANES`-wide scatter, or slot
+    /// by slot if the batch wraps around the end of the buffer or `T` is not
+    /// vectorized; each slot's sequence is then published individually.
+    /// The scatter strides over cache-line slots, so it still stores each
+    /// lane on its own (see the type docs).
     unsafe fn store_batch_simd(&self, head: usize, items: &[T]) {
-        // Convert to u64 for SIMD processing
-        let u64_items: [u64; 4] = [
-            items[0].to_u64(),
-            items[1].to_u64(), 
-            items[2].to_u64(),
-            items[3].to_u64(),
-        ];
-        let _simd_data = u64x4::from_array(u64_items);
-        
-        for (i, &value) in items.iter().enumerate().take(4) {
-            let slot_idx = (head.wrapping_add(i)) & self.mask;
-            let slot = &self.buffer[slot_idx];
-            
-            // Store the data
-            unsafe {
-                (*slot.data.get()).write(value);
+        let batch_size = items.len().min(LANES);
+        if T::VECTORIZED && self.is_contiguous(head, batch_size) {
+            unsafe { self.scatter_payloads(head, &items[..batch_size]) }
+        } else {
+            for (i, &item) in items[..batch_size].iter().enumerate() {
+                unsafe { self.buffer[advance(head, i) & self.mask].write(item) };
             }
-            
-            // Update sequence to signal data is ready
-            slot.sequence.store(
-                (head + i).wrapping_add(1),
-                Ordering::Release,
-            );
+            if T::VECTORIZED {
+                self.record_wrapped_batch();
+            }
+        }
+        
+        for i in 0..batch_size {
+            let pos = advance(head, i);
+            self.buffer[pos & self.mask]
+                .sequence
+                .store(next_seq(pos), Ordering::Release);
+        }
+        self.record_occupancy();
+    }
+    
+    unsafe fn scatter_payloads(&self, head: usize, items: &[T]) {
+        let mut lanes = [0u64; LANES];
+        for (lane, item) in lanes.iter_mut().zip(items) {
+            *lane = item.to_lane();
+        }
+        
+        // The claimed slots are ours until their sequences are published
+        unsafe {
+            Simd::from_array(lanes)
+                .scatter_select_ptr(self.payload_ptrs(head), Self::batch_lanes(items.len()));
         }
     }
     
     /// Load batch data using SIMD operations
+    /// 
+    /// The payloads are read with a single `LANES`-wide gather, or slot by
+    /// slot if the batch wraps around the end of the buffer or `T` is not
+    /// vectorized, before any of the slots is released back to producers.
+    /// Like the scatter, the gather still loads each lane on its own.
     unsafe fn load_batch_simd(&self, tail: usize, buffer: &mut [T]) {
-        let mut u64_buffer = [0u64; 4];
-        
-        for (i, buffer_slot) in buffer.iter_mut().enumerate().take(4) {
-            let slot_idx = (tail.wrapping_add(i)) & self.mask;
-            let slot = &self.buffer[slot_idx];
-            
-            // Load the data
-            unsafe {
-                let value = (*slot.data.get()).assume_init_read();
-                *buffer_slot = value;
-                u64_buffer[i] = value.to_u64();
+        let batch_size = buffer.len().min(LANES);
+        if T::VECTORIZED && self.is_contiguous(tail, batch_size) {
+            unsafe { self.gather_payloads(tail, &mut buffer[..batch_size]) }
+        } else {
+            for (i, dst) in buffer[..batch_size].iter_mut().enumerate() {
+                *dst = unsafe { self.buffer[advance(tail, i) & self.mask].read() };
+            }
+            if T::VECTORIZED {
+                self.record_wrapped_batch();
             }
-            
-            // Mark slot as available for producers
-            slot.sequence.store(
-                (tail + i).wrapping_add(self.capacity),
-                Ordering::Release,
-            );
         }
         
-        // Use SIMD for the loaded data (for future optimizations)
-        let _simd_data = u64x4::from_array(u64_buffer);
+        for i in 0..batch_size {
+            let pos = advance(tail, i);
+            self.buffer[pos & self.mask]
+                .sequence
+                .store(free_seq(pos, self.capacity), Ordering::Release);
+        }
+    }
+    
+    unsafe fn gather_payloads(&self, tail: usize, buffer: &mut [T]) {
+        let values = unsafe {
+            Simd::<u64, LANES>::gather_select_ptr(
+                self.payload_ptrs(tail).cast_const(),
+                Self::batch_lanes(buffer.len()),
+                Simd::splat(0),
+            )
+        };
+        for (dst, value) in buffer.iter_mut().zip(values.to_array()) {
+            *dst = T::from_lane(value);
+        }
     }
     
     /// Internal single-element send implementation
     fn send_single_internal(&self, item: T) -> Result<(), T> {
+        let backoff = Backoff::default();
+        let mut attempt = 0u32;
         loop {
             let head = self.producer_pos.head.load(Ordering::Relaxed);
             let slot = &self.buffer[head & self.mask];
@@ -357,33 +559,40 @@ impl<T: Simd64Bit> SimdMpmcQueue<T> {
                 std::cmp::Ordering::Equal => {
                     match self.producer_pos.head.compare_exchange_weak(
                         head,
-                        head.wrapping_add(1),
+                        next_seq(head),
                         Ordering::Relaxed,
                         Ordering::Relaxed,
                     ) {
                         Ok(_) => {
                             unsafe {
-                                (*slot.data.get()).write(item);
+                                slot.write(item);
                             }
-                            slot.sequence.store(expected_seq.wrapping_add(1), Ordering::Release);
+                            slot.sequence.store(next_seq(expected_seq), Ordering::Release);
+                            self.record_occupancy();
                             return Ok(());
                         }
                         Err(_) => {
-                            std::hint::spin_loop();
+                            backoff.wait(attempt);
+                            attempt = attempt.saturating_add(1);
                             continue;
                         }
                     }
                 }
                 std::cmp::Ordering::Less => {
                     let tail = self.consumer_pos.tail.load(Ordering::Acquire);
-                    if head.wrapping_sub(tail) >= self.capacity {
+                    if depth(head, tail) >= self.capacity {
                         return Err(item);
                     }
-                    std::hint::spin_loop();
+                    backoff.wait(attempt);
+                    attempt = attempt.saturating_add(1);
                     continue;
                 }
                 std::cmp::Ordering::Greater => {
-                    std::hint::spin_loop();
+                    // Another producer claimed `head`, wait until we see it move
+                    while self.producer_pos.head.load(Ordering::Acquire) == head {
+                        backoff.wait(attempt);
+                        attempt = attempt.saturating_add(1);
+                    }
                     continue;
                 }
             }
@@ -392,31 +601,34 @@ impl<T: Simd64Bit> SimdMpmcQueue<T> {
     
     /// Internal single-element receive implementation
     fn recv_single_internal(&self) -> Option<T> {
+        let backoff = Backoff::default();
+        let mut attempt = 0u32;
         loop {
             let tail = self.consumer_pos.tail.load(Ordering::Relaxed);
             let slot = &self.buffer[tail & self.mask];
             
             let seq = slot.sequence.load(Ordering::Acquire);
-            let expected_seq = tail.wrapping_add(1);
+            let expected_seq = next_seq(tail);
             
             match seq.cmp(&expected_seq) {
                 std::cmp::Ordering::Equal => {
                     match self.consumer_pos.tail.compare_exchange_weak(
                         tail,
-                        tail.wrapping_add(1),
+                        next_seq(tail),
                         Ordering::Relaxed,
                         Ordering::Relaxed,
                     ) {
                         Ok(_) => {
-                            let item = unsafe { (*slot.data.get()).assume_init_read() };
+                            let item = unsafe { slot.read() };
                             slot.sequence.store(
-                                tail.wrapping_add(self.capacity),
+                                free_seq(tail, self.capacity),
                                 Ordering::Release,
                             );
                             return Some(item);
                         }
                         Err(_) => {
-                            std::hint::spin_loop();
+                            backoff.wait(attempt);
+                            attempt = attempt.saturating_add(1);
                             continue;
                         }
                     }
@@ -425,13 +637,63 @@ impl<T: Simd64Bit> SimdMpmcQueue<T> {
                     return None;
                 }
                 std::cmp::Ordering::Greater => {
-                    std::hint::spin_loop();
+                    // Another consumer claimed `tail`, wait until we see it move
+                    while self.consumer_pos.tail.load(Ordering::Acquire) == tail {
+                        backoff.wait(attempt);
+                        attempt = attempt.saturating_add(1);
+                    }
                     continue;
                 }
             }
         }
     }
     
+    /// Send a single SIMD batch of up to `LANES` items, all or nothing
+    /// 
+    /// Only the first `min(items.len(), LANES)` items are considered. Unlike `send`,
+    /// there is no per-item fallback: if those slots cannot all be claimed at
+    /// once (queue too full, or another producer won the race), nothing is sent.
+    /// Returns `Ok(n)` with the number of items sent, or `Err(n)` with the
+    /// number of items that were not sent.
+    pub fn send_batch(&self, items: &[T]) -> Result<usize, usize> {
+        let batch = &items[..std::cmp::min(items.len(), LANES)];
+        if batch.is_empty() {
+            return Ok(0);
+        }
+        
+        let head = self.producer_pos.head.load(Ordering::Relaxed);
+        if self.try_claim_batch_producer(head, batch.len()) {
+            unsafe {
+                self.store_batch_simd(head, batch);
+            }
+            Ok(batch.len())
+        } else {
+            Err(batch.len())
+        }
+    }
+    
+    /// Receive a single SIMD batch of up to `LANES` items, all or nothing
+    /// 
+    /// Fills the first `min(buffer.len(), LANES)` entries of `buffer` only if that
+    /// many items can be claimed at once, and returns how many were received
+    /// (0 otherwise). There is no per-item fallback, see `send_batch`.
+    pub fn recv_batch(&self, buffer: &mut [T]) -> usize {
+        let batch_size = std::cmp::min(buffer.len(), LANES);
+        if batch_size == 0 {
+            return 0;
+        }
+        
+        let tail = self.consumer_pos.tail.load(Ordering::Relaxed);
+        if self.try_claim_batch_consumer(tail, batch_size) {
+            unsafe {
+                self.load_batch_simd(tail, &mut buffer[..batch_size]);
+            }
+            batch_size
+        } else {
+            0
+        }
+    }
+    
     /// Send single item
     pub fn send_one(&self, item: T) -> Result<(), T> {
         self.send_single_internal(item)
@@ -442,6 +704,12 @@ impl<T: Simd64Bit> SimdMpmcQueue<T> {
         self.recv_single_internal()
     }
     
+    /// Returns the batch size `send` and `recv` claim per SIMD operation,
+    /// i.e. `LANES`
+    pub fn batch_width(&self) -> usize {
+        LANES
+    }
+    
     /// Returns the capacity of the queue
     pub fn capacity(&self) -> usize {
         self.capacity
@@ -456,38 +724,81 @@ impl<T: Simd64Bit> SimdMpmcQueue<T> {
     
     /// Returns true if the queue is full
     pub fn is_full(&self) -> bool {
-        let head = self.producer_pos.head.load(Ordering::Acquire);
-        let tail = self.consumer_pos.tail.load(Ordering::Acquire);
-        head.wrapping_sub(tail) >= self.capacity
+        self.len() >= self.capacity
     }
     
     /// Returns the approximate number of items in the queue
     pub fn len(&self) -> usize {
-        let head = self.producer_pos.head.load(Ordering::Acquire);
+        // Tail first, so a consumer racing ahead cannot make the depth
+        // wrap; clamped since producers may move on in between too
         let tail = self.consumer_pos.tail.load(Ordering::Acquire);
-        head.wrapping_sub(tail)
+        let head = self.producer_pos.head.load(Ordering::Acquire);
+        depth(head, tail).min(self.capacity)
+    }
+    
+    /// Returns the largest `len()` observed right after a send since the
+    /// queue was created or `reset_high_water_mark` was called
+    /// 
+    /// See `MpmcQueue::high_water_mark`.
+    #[cfg(feature = "metrics")]
+    pub fn high_water_mark(&self) -> usize {
+        self.high_water_mark.load(Ordering::Relaxed)
+    }
+    
+    /// Restarts high-water-mark tracking from the current `len()`
+    #[cfg(feature = "metrics")]
+    pub fn reset_high_water_mark(&self) {
+        self.high_water_mark.store(self.len(), Ordering::Relaxed);
+    }
+    
+    /// Raises the high-water mark to the current length; a no-op without
+    /// the `metrics` feature
+    #[inline]
+    fn record_occupancy(&self) {
+        #[cfg(feature = "metrics")]
+        self.high_water_mark.fetch_max(self.len(), Ordering::Relaxed);
+    }
+    
+    /// Returns how many batches wrapped around the end of the buffer and
+    /// were moved slot by slot instead of with one scatter or gather
+    /// 
+    /// Batches stay aligned while every send and receive moves a multiple
+    /// of `LANES` items; single-item operations shift later batches across
+    /// the wrap. A count that grows with traffic means the mix of batch
+    /// and single operations is costing vectorization.
+    #[cfg(feature = "metrics")]
+    pub fn wrapped_batches(&self) -> u64 {
+        self.wrapped_batches.load(Ordering::Relaxed)
+    }
+    
+    /// Counts a batch that took the slot-by-slot path; a no-op without the
+    /// `metrics` feature
+    #[inline]
+    fn record_wrapped_batch(&self) {
+        #[cfg(feature = "metrics")]
+        self.wrapped_batches.fetch_add(1, Ordering::Relaxed);
     }
 }
 
-unsafe impl<T: Simd64Bit> Send for SimdMpmcQueue<T> {}
-unsafe impl<T: Simd64Bit> Sync for SimdMpmcQueue<T> {}
+unsafe impl<T: SimdElement, const LANES: usize> Send for SimdMpmcQueue<T, LANES> {}
+unsafe impl<T: SimdElement, const LANES: usize> Sync for SimdMpmcQueue<T, LANES> {}
 
 /// Producer handle for SIMD queue
-pub struct SimdProducer<T> {
-    queue: Arc<SimdMpmcQueue<T>>,
+pub struct SimdProducer<T, const LANES: usize = 4> {
+    queue: Arc<SimdMpmcQueue<T, LANES>>,
 }
 
 /// Consumer handle for SIMD queue
-pub struct SimdConsumer<T> {
-    queue: Arc<SimdMpmcQueue<T>>,
+pub struct SimdConsumer<T, const LANES: usize = 4> {
+    queue: Arc<SimdMpmcQueue<T, LANES>>,
 }
 
-impl<T: Simd64Bit> SimdProducer<T> {
-    pub fn new(queue: Arc<SimdMpmcQueue<T>>) -> Self {
+impl<T: SimdElement, const LANES: usize> SimdProducer<T, LANES> {
+    pub fn new(queue: Arc<SimdMpmcQueue<T, LANES>>) -> Self {
         Self { queue }
     }
     
-    pub fn send(&self, items: &[T]) -> Result<usize, Vec<T>> {
+    pub fn send(&self, items: &[T]) -> Result<usize, usize> {
         self.queue.send(items)
     }
     
@@ -504,8 +815,8 @@ impl<T: Simd64Bit> SimdProducer<T> {
     }
 }
 
-impl<T: Simd64Bit> SimdConsumer<T> {
-    pub fn new(queue: Arc<SimdMpmcQueue<T>>) -> Self {
+impl<T: SimdElement, const LANES: usize> SimdConsumer<T, LANES> {
+    pub fn new(queue: Arc<SimdMpmcQueue<T, LANES>>) -> Self {
         Self { queue }
     }
     
@@ -526,7 +837,7 @@ impl<T: Simd64Bit> SimdConsumer<T> {
     }
 }
 
-impl<T: Simd64Bit> Clone for SimdProducer<T> {
+impl<T: SimdElement, const LANES: usize> Clone for SimdProducer<T, LANES> {
     fn clone(&self) -> Self {
         Self {
             queue: Arc::clone(&self.queue),
@@ -534,7 +845,7 @@ impl<T: Simd64Bit> Clone for SimdProducer<T> {
     }
 }
 
-impl<T: Simd64Bit> Clone for SimdConsumer<T> {
+impl<T: SimdElement, const LANES: usize> Clone for SimdConsumer<T, LANES> {
     fn clone(&self) -> Self {
         Self {
             queue: Arc::clone(&self.queue),
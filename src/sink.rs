@@ -0,0 +1,111 @@
+//! `futures::Sink` adapter for producers (`futures` feature).
+
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_sink::Sink;
+
+use crate::Producer;
+
+/// A `Sink` that sends into the queue and waits for space when it is full.
+///
+/// `poll_ready` returns `Pending` while the queue is full and registers the
+/// task's waker, which fires when a consumer frees a slot, so
+/// `sink.send(item).await` (from `futures::SinkExt`) waits for capacity
+/// instead of failing.
+///
+/// The sink buffers at most one item: `start_send` stores it and the next
+/// `poll_ready` or `poll_flush` pushes it into the queue. This keeps
+/// `start_send` infallible even though other producers may take the slot
+/// that made `poll_ready` succeed. `poll_flush` completes once the buffered
+/// item is in the queue, not once it has been received.
+pub struct ProducerSink<T> {
+    producer: Producer<T>,
+    pending: Option<T>,
+    key: Option<u64>,
+}
+
+impl<T: Send> ProducerSink<T> {
+    /// Wraps a producer handle.
+    pub fn new(producer: Producer<T>) -> Self {
+        Self {
+            producer,
+            pending: None,
+            key: None,
+        }
+    }
+
+    /// Returns the producer handle. An item still buffered in the sink is
+    /// dropped; flush first to avoid that.
+    pub fn into_inner(self) -> Producer<T> {
+        let mut this = std::mem::ManuallyDrop::new(self);
+        let this = &mut *this;
+        this.producer.queue.not_full.cancel(&mut this.key);
+        drop(this.pending.take());
+        unsafe { std::ptr::read(&this.producer) }
+    }
+
+    // Pushes the buffered item into the queue, waiting for space if needed
+    fn poll_send_pending(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let Some(item) = self.pending.take() else {
+            return Poll::Ready(());
+        };
+        let not_full = &self.producer.queue.not_full;
+        let item = match self.producer.send_now(item) {
+            Ok(()) => {
+                // Drop a registration left over from an earlier wait
+                not_full.unregister(&mut self.key);
+                return Poll::Ready(());
+            }
+            Err(item) => item,
+        };
+
+        // Register before the final attempt so a slot freed in between
+        // either shows up in the retry or wakes the task
+        not_full.register(&mut self.key, cx.waker());
+        match self.producer.send_now(item) {
+            Ok(()) => {
+                not_full.unregister(&mut self.key);
+                Poll::Ready(())
+            }
+            Err(item) => {
+                self.pending = Some(item);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+// The buffered item is never pinned, so the sink can move freely
+impl<T> Unpin for ProducerSink<T> {}
+
+impl<T: Send> Sink<T> for ProducerSink<T> {
+    type Error = Infallible;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        self.get_mut().poll_send_pending(cx).map(Ok)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: T) -> Result<(), Infallible> {
+        let this = self.get_mut();
+        assert!(this.pending.is_none(), "start_send called without poll_ready");
+        this.pending = Some(item);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        self.get_mut().poll_send_pending(cx).map(Ok)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Infallible>> {
+        self.poll_flush(cx)
+    }
+}
+
+impl<T> Drop for ProducerSink<T> {
+    fn drop(&mut self) {
+        // Pass on a wakeup this sink received but will not act on
+        self.producer.queue.not_full.cancel(&mut self.key);
+    }
+}
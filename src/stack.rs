@@ -0,0 +1,268 @@
+//! Bounded lock-free LIFO stack.
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use crate::wait_strategy::{Backoff, WaitStrategy};
+
+// Index that ends a list
+const NIL: u32 = u32::MAX;
+
+/// A bounded MPMC stack: `pop` returns the item pushed most recently.
+///
+/// Suits object pools and depth-first work, where the most recently
+/// returned item is the one most likely to still be in cache. It is a
+/// Treiber stack whose nodes live in a fixed pool allocated up front: a
+/// pushed item is written into a free node, and a popped node goes back to
+/// the free list instead of being deallocated. Since nodes are never freed
+/// while the stack is alive, a thread still reading a node another thread
+/// just popped reads valid memory.
+///
+/// ABA (a node popped and pushed back between a thread's read of the top
+/// and its `compare_exchange`) is caught by a 32-bit tag stored next to the
+/// top index and bumped by every push and pop. It could only slip through
+/// if one thread stalled for exactly a multiple of 2^32 operations.
+///
+/// Capacity is exact (not rounded) and must be below `u32::MAX`.
+pub struct MpmcStack<T> {
+    nodes: Box<[Node<T>]>,
+    top: TaggedList,  // pushed items, most recent first
+    free: TaggedList, // unused nodes
+    len: AtomicUsize,
+}
+
+#[repr(align(64))] // Align to cache line to avoid false sharing
+#[cfg_attr(all(target_arch = "aarch64", target_vendor = "apple"), repr(align(128)))]
+#[cfg_attr(target_arch = "s390x", repr(align(256)))]
+struct Node<T> {
+    next: AtomicUsize, // index of the node below, or NIL
+    data: UnsafeCell<MaybeUninit<T>>,
+}
+
+// Head of a singly linked list of nodes, packed as (tag << 32) | index
+#[repr(align(64))]
+#[cfg_attr(all(target_arch = "aarch64", target_vendor = "apple"), repr(align(128)))]
+#[cfg_attr(target_arch = "s390x", repr(align(256)))]
+struct TaggedList {
+    head: AtomicU64,
+}
+
+impl TaggedList {
+    fn new(index: u32) -> Self {
+        Self {
+            head: AtomicU64::new(u64::from(index)),
+        }
+    }
+}
+
+fn pack(tag: u32, index: u32) -> u64 {
+    (u64::from(tag) << 32) | u64::from(index)
+}
+
+fn unpack(head: u64) -> (u32, u32) {
+    ((head >> 32) as u32, head as u32)
+}
+
+impl<T: Send> MpmcStack<T> {
+    /// Creates an empty stack with room for `capacity` items.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "Capacity must be greater than 0");
+        assert!(capacity < NIL as usize, "Capacity must be below u32::MAX");
+
+        // Every node starts on the free list, each linking to the next
+        let nodes = (0..capacity)
+            .map(|i| Node {
+                next: AtomicUsize::new(if i + 1 < capacity { i + 1 } else { NIL as usize }),
+                data: UnsafeCell::new(MaybeUninit::uninit()),
+            })
+            .collect();
+
+        Self {
+            nodes,
+            top: TaggedList::new(NIL),
+            free: TaggedList::new(0),
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    /// Creates a stack and returns a producer and a consumer handle for it.
+    ///
+    /// The stack lives behind an `Arc` shared by the handles; clone them
+    /// for more producers or consumers.
+    pub fn with_handles(capacity: usize) -> (StackProducer<T>, StackConsumer<T>) {
+        let stack = Arc::new(Self::new(capacity));
+        (StackProducer { stack: Arc::clone(&stack) }, StackConsumer { stack })
+    }
+
+    /// Pushes an item on top of the stack.
+    ///
+    /// Returns the item back if the stack is full.
+    pub fn push(&self, item: T) -> Result<(), T> {
+        let Some(index) = self.pop_node(&self.free) else {
+            return Err(item);
+        };
+        // The node is ours until it is linked into `top`
+        unsafe {
+            (*self.nodes[index as usize].data.get()).write(item);
+        }
+        // Counted before linking: a `pop` that unlinks the node right away
+        // is ordered after this by `top`, so `len` never goes below 0
+        self.len.fetch_add(1, Ordering::Relaxed);
+        self.push_node(&self.top, index);
+        Ok(())
+    }
+
+    /// Pops the most recently pushed item, or returns None if the stack is
+    /// empty.
+    pub fn pop(&self) -> Option<T> {
+        let index = self.pop_node(&self.top)?;
+        self.len.fetch_sub(1, Ordering::Relaxed);
+        let item = unsafe { (*self.nodes[index as usize].data.get()).assume_init_read() };
+        self.push_node(&self.free, index);
+        Some(item)
+    }
+
+    /// Returns the capacity of the stack.
+    pub fn capacity(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the approximate number of items on the stack.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    /// Returns true if the stack is empty.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn is_empty(&self) -> bool {
+        unpack(self.top.head.load(Ordering::Acquire)).1 == NIL
+    }
+
+    // Unlinks the first node of `list`, returning its index
+    fn pop_node(&self, list: &TaggedList) -> Option<u32> {
+        let backoff = Backoff::default();
+        let mut attempt = 0u32;
+        loop {
+            // Acquire: pairs with the Release that linked the node, so its
+            // `next` and data are visible
+            let head = list.head.load(Ordering::Acquire);
+            let (tag, index) = unpack(head);
+            if index == NIL {
+                return None;
+            }
+            // May be stale if the node was popped meanwhile; the tag then
+            // makes the exchange below fail
+            let next = self.nodes[index as usize].next.load(Ordering::Relaxed) as u32;
+            if list
+                .head
+                .compare_exchange_weak(head, pack(tag.wrapping_add(1), next), Ordering::Acquire, Ordering::Relaxed)
+                .is_ok()
+            {
+                return Some(index);
+            }
+            backoff.wait(attempt);
+            attempt = attempt.saturating_add(1);
+        }
+    }
+
+    // Links the node at `index`, which the caller owns, in front of `list`
+    fn push_node(&self, list: &TaggedList, index: u32) {
+        let backoff = Backoff::default();
+        let mut attempt = 0u32;
+        let node = &self.nodes[index as usize];
+        loop {
+            let head = list.head.load(Ordering::Relaxed);
+            let (tag, first) = unpack(head);
+            node.next.store(first as usize, Ordering::Relaxed);
+            // Release: publishes the node's data and `next` to the next pop
+            if list
+                .head
+                .compare_exchange_weak(head, pack(tag.wrapping_add(1), index), Ordering::Release, Ordering::Relaxed)
+                .is_ok()
+            {
+                return;
+            }
+            backoff.wait(attempt);
+            attempt = attempt.saturating_add(1);
+        }
+    }
+}
+
+unsafe impl<T: Send> Send for MpmcStack<T> {}
+unsafe impl<T: Send> Sync for MpmcStack<T> {}
+
+impl<T> Drop for MpmcStack<T> {
+    fn drop(&mut self) {
+        let mut index = unpack(*self.top.head.get_mut()).1;
+        while index != NIL {
+            let node = &mut self.nodes[index as usize];
+            unsafe {
+                node.data.get_mut().assume_init_drop();
+            }
+            index = *node.next.get_mut() as u32;
+        }
+    }
+}
+
+/// A producer handle for an `MpmcStack`, created by `MpmcStack::with_handles`.
+pub struct StackProducer<T> {
+    stack: Arc<MpmcStack<T>>,
+}
+
+impl<T: Send> StackProducer<T> {
+    /// Pushes an item, returning it back if the stack is full.
+    pub fn push(&self, item: T) -> Result<(), T> {
+        self.stack.push(item)
+    }
+
+    /// Returns the approximate number of items on the stack.
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns true if the stack is empty.
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+}
+
+impl<T> Clone for StackProducer<T> {
+    fn clone(&self) -> Self {
+        Self { stack: Arc::clone(&self.stack) }
+    }
+}
+
+/// A consumer handle for an `MpmcStack`, created by `MpmcStack::with_handles`.
+pub struct StackConsumer<T> {
+    stack: Arc<MpmcStack<T>>,
+}
+
+impl<T: Send> StackConsumer<T> {
+    /// Pops the most recently pushed item, or returns None if the stack is
+    /// empty.
+    pub fn pop(&self) -> Option<T> {
+        self.stack.pop()
+    }
+
+    /// Returns the approximate number of items on the stack.
+    pub fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// Returns true if the stack is empty.
+    pub fn is_empty(&self) -> bool {
+        self.stack.is_empty()
+    }
+}
+
+impl<T> Clone for StackConsumer<T> {
+    fn clone(&self) -> Self {
+        Self { stack: Arc::clone(&self.stack) }
+    }
+}
@@ -0,0 +1,59 @@
+//! Atomics and `UnsafeCell` behind `MpmcQueue`, swapped for loom's
+//! model-checked versions when built with `RUSTFLAGS="--cfg loom"`.
+//!
+//! This is a cfg rather than a Cargo feature because loom's types panic
+//! outside a `loom::model`: a feature would be switched on by
+//! `--all-features` and break every ordinary test. The models live in the
+//! `loom_tests` module of `src/main.rs`:
+//!
+//! ```text
+//! RUSTFLAGS="--cfg loom" cargo test --release --bin mpmc-std loom_tests
+//! ```
+//!
+//! loom's `UnsafeCell` only hands out its pointer inside a closure, so it
+//! can track every access; the std build wraps `core::cell::UnsafeCell` in
+//! the same `with`/`with_mut` interface so the queue compiles against both.
+
+#[cfg(all(feature = "metrics", not(loom)))]
+pub(crate) use core::sync::atomic::AtomicU64;
+#[cfg(not(loom))]
+pub(crate) use core::sync::atomic::AtomicUsize;
+#[cfg(all(feature = "compact-seq", not(loom)))]
+pub(crate) use core::sync::atomic::AtomicU32 as AtomicSeq;
+#[cfg(all(not(feature = "compact-seq"), not(loom)))]
+pub(crate) use core::sync::atomic::AtomicUsize as AtomicSeq;
+
+#[cfg(all(feature = "metrics", loom))]
+pub(crate) use loom::sync::atomic::AtomicU64;
+#[cfg(loom)]
+pub(crate) use loom::sync::atomic::AtomicUsize;
+#[cfg(all(feature = "compact-seq", loom))]
+pub(crate) use loom::sync::atomic::AtomicU32 as AtomicSeq;
+#[cfg(all(not(feature = "compact-seq"), loom))]
+pub(crate) use loom::sync::atomic::AtomicUsize as AtomicSeq;
+#[cfg(loom)]
+pub(crate) use loom::cell::UnsafeCell;
+
+// Transparent so an array of cells can be viewed as an array of values, see
+// `PackedMpmcQueue::recv_zero_copy_wrapping`
+#[cfg(not(loom))]
+#[repr(transparent)]
+pub(crate) struct UnsafeCell<T>(core::cell::UnsafeCell<T>);
+
+#[cfg(not(loom))]
+impl<T> UnsafeCell<T> {
+    #[inline]
+    pub(crate) const fn new(value: T) -> Self {
+        Self(core::cell::UnsafeCell::new(value))
+    }
+
+    #[inline]
+    pub(crate) fn with<R>(&self, f: impl FnOnce(*const T) -> R) -> R {
+        f(self.0.get())
+    }
+
+    #[inline]
+    pub(crate) fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
+        f(self.0.get())
+    }
+}
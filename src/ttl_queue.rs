@@ -0,0 +1,101 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::MpmcQueue;
+
+/// MPMC queue whose items can expire before they are received.
+///
+/// For caches and pipelines where stale data is worse than none: an item
+/// sent with `send_with_ttl` is only handed out while its time-to-live
+/// lasts. `recv` drops expired items it comes across and moves on to the
+/// next one, so each slot carries the item's expiry time next to it.
+///
+/// Expired items are only noticed, and dropped, when a receive reaches
+/// them: until then they hold their slots and count in `len`. Every item a
+/// `recv` skips is removed for good, so a queue holding only expired items
+/// is emptied by one `recv`, which then returns None.
+pub struct TtlMpmcQueue<T> {
+    queue: MpmcQueue<Timed<T>>,
+    expired: AtomicU64,
+}
+
+struct Timed<T> {
+    item: T,
+    // None for items that never expire
+    expires_at: Option<Instant>,
+}
+
+impl<T: Send> TtlMpmcQueue<T> {
+    /// Creates a queue; capacity is rounded up to a power of 2 as in
+    /// `MpmcQueue::new`.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            queue: MpmcQueue::new(capacity),
+            expired: AtomicU64::new(0),
+        }
+    }
+
+    /// Sends an item that never expires.
+    ///
+    /// Returns the item back if the queue is full.
+    pub fn send(&self, item: T) -> Result<(), T> {
+        self.send_timed(item, None)
+    }
+
+    /// Sends an item that is dropped instead of received once `ttl` has
+    /// passed.
+    ///
+    /// Returns the item back if the queue is full.
+    pub fn send_with_ttl(&self, item: T, ttl: Duration) -> Result<(), T> {
+        // A TTL too long to represent never runs out
+        self.send_timed(item, Instant::now().checked_add(ttl))
+    }
+
+    fn send_timed(&self, item: T, expires_at: Option<Instant>) -> Result<(), T> {
+        self.queue.send(Timed { item, expires_at }).map_err(|timed| timed.item)
+    }
+
+    /// Receives the oldest item that has not expired, dropping the expired
+    /// ones in front of it.
+    ///
+    /// Returns None once the queue is empty, including when every item left
+    /// in it had expired.
+    pub fn recv(&self) -> Option<T> {
+        let mut now = None;
+        while let Some(timed) = self.queue.recv() {
+            let Some(expires_at) = timed.expires_at else {
+                return Some(timed.item);
+            };
+            if expires_at > *now.get_or_insert_with(Instant::now) {
+                return Some(timed.item);
+            }
+            self.expired.fetch_add(1, Ordering::Relaxed);
+        }
+        None
+    }
+
+    /// Returns how many items were dropped on expiry so far.
+    pub fn expired(&self) -> u64 {
+        self.expired.load(Ordering::Relaxed)
+    }
+
+    /// Returns the approximate number of items in the queue, including
+    /// expired ones not yet dropped.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Returns true if the queue is empty.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Returns the capacity of the queue.
+    pub fn capacity(&self) -> usize {
+        self.queue.capacity()
+    }
+}
@@ -0,0 +1,99 @@
+//! Differential-testing wrapper for `MpmcQueue` (testing tool, `validating` feature).
+//!
+//! `ValidatingQueue` applies every operation both to a real `MpmcQueue` and
+//! to a reference model (a `VecDeque` with the same capacity) and panics as
+//! soon as their observable behaviour differs: a send accepted by one and
+//! rejected by the other, a recv returning a different item, or a mismatched
+//! length. This checks the lock-free queue against the FIFO semantics it is
+//! meant to implement.
+//!
+//! Each operation runs under the model's lock, so concurrent callers are
+//! serialized into a single order that both sides must agree on. That makes
+//! it suitable for single-threaded and lightly concurrent tests; it is not a
+//! substitute for loom or stress testing of the lock-free paths themselves,
+//! and not meant for production use.
+
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::sync::Mutex;
+
+use crate::MpmcQueue;
+
+/// An `MpmcQueue` checked operation-by-operation against a `VecDeque` model.
+pub struct ValidatingQueue<T> {
+    queue: MpmcQueue<T>,
+    model: Mutex<VecDeque<T>>,
+}
+
+impl<T: Send + Clone + PartialEq + Debug> ValidatingQueue<T> {
+    /// Creates a validating queue; capacity is rounded up as in `MpmcQueue::new`.
+    pub fn new(capacity: usize) -> Self {
+        let queue = MpmcQueue::new(capacity);
+        let model = VecDeque::with_capacity(queue.capacity());
+        Self {
+            queue,
+            model: Mutex::new(model),
+        }
+    }
+
+    /// Sends to both the queue and the model.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the queue and the model disagree on whether the item fits.
+    pub fn send(&self, item: T) -> Result<(), T> {
+        let mut model = self.model.lock().unwrap();
+        let model_full = model.len() >= self.queue.capacity();
+        let result = self.queue.send(item.clone());
+
+        match (&result, model_full) {
+            (Ok(()), false) => model.push_back(item),
+            (Err(rejected), true) => assert_eq!(rejected, &item, "send returned a different item"),
+            (Ok(()), true) => panic!("send accepted {:?} but the model is full", item),
+            (Err(_), false) => panic!(
+                "send rejected {:?} with {} of {} slots used",
+                item,
+                model.len(),
+                self.queue.capacity()
+            ),
+        }
+        self.check_len(&model);
+        result
+    }
+
+    /// Receives from both the queue and the model.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the queue returns anything other than the model's front item.
+    pub fn recv(&self) -> Option<T> {
+        let mut model = self.model.lock().unwrap();
+        let item = self.queue.recv();
+        assert_eq!(item, model.pop_front(), "recv diverged from FIFO order");
+        self.check_len(&model);
+        item
+    }
+
+    /// Returns the number of items, after checking the queue against the model.
+    pub fn len(&self) -> usize {
+        let model = self.model.lock().unwrap();
+        self.check_len(&model);
+        model.len()
+    }
+
+    /// Returns true if the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the capacity of the queue.
+    pub fn capacity(&self) -> usize {
+        self.queue.capacity()
+    }
+
+    fn check_len(&self, model: &VecDeque<T>) {
+        assert_eq!(self.queue.len(), model.len(), "queue length diverged from the model");
+        assert_eq!(self.queue.is_empty(), model.is_empty());
+        assert_eq!(self.queue.is_full(), model.len() == self.queue.capacity());
+    }
+}
@@ -0,0 +1,364 @@
+//! Parking support for the blocking and async queue operations.
+//!
+//! Parking needs `std`. Without it the queue has no blocking or async
+//! operations, nothing ever waits, and `WaitList` is an empty stand-in
+//! whose notifications compile away.
+
+#[cfg(feature = "std")]
+use std::cell::Cell;
+#[cfg(feature = "std")]
+use std::cmp::Reverse;
+#[cfg(feature = "std")]
+use std::collections::VecDeque;
+#[cfg(feature = "std")]
+use std::sync::atomic::{fence, AtomicBool, AtomicUsize, Ordering};
+#[cfg(feature = "std")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "std")]
+use std::task::{Context, Poll, Wake, Waker};
+#[cfg(feature = "std")]
+use std::thread::{self, Thread};
+#[cfg(feature = "std")]
+use std::time::Instant;
+
+/// Which parked consumer a send wakes first, chosen with
+/// `MpmcQueue::with_wake_policy`.
+///
+/// Every parked thread and waiting task has its own place in the queue's
+/// wait list, so the order is decided here rather than by the OS.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WakePolicy {
+    /// The consumer that has waited longest. Fair: no consumer waits behind
+    /// more than the ones that were already waiting when it parked.
+    #[default]
+    Fifo,
+    /// The consumer that parked most recently. Keeps the same few consumers
+    /// busy with their data still in cache, at the price of fairness: under
+    /// a light load, consumers parked early may wait until the load rises.
+    Lifo,
+    /// The consumer with the highest priority, set per thread with
+    /// `set_wake_priority`; among equal priorities, the one that has waited
+    /// longest. Picking one scans the wait list, which costs a little with
+    /// many parked consumers, and low priorities can starve.
+    Priority,
+}
+
+#[cfg(feature = "std")]
+thread_local! {
+    // Priority of the waiters this thread registers, for `WakePolicy::Priority`
+    static WAKE_PRIORITY: Cell<u8> = const { Cell::new(0) };
+}
+
+/// Sets the priority the calling thread waits with under
+/// `WakePolicy::Priority`; higher priorities are woken first.
+///
+/// Applies to the blocking receives this thread makes from then on, and to
+/// async receives polled on it. Threads start at 0. Queues with another
+/// wake policy ignore it.
+#[cfg(feature = "std")]
+pub fn set_wake_priority(priority: u8) {
+    WAKE_PRIORITY.with(|current| current.set(priority));
+}
+
+/// Threads parked, or tasks waiting, until some condition on the queue may
+/// have changed.
+///
+/// Waiters announce themselves in `sleepers` before their final re-check, and
+/// notifiers check `sleepers` after publishing their change, both around a
+/// SeqCst fence: either the notifier sees the waiter and wakes it, or the
+/// waiter's re-check sees the change. Notifiers only take the lock when
+/// someone is actually waiting, so the uncontended cost is a fence and a load.
+///
+/// Blocked threads and async tasks alike register a `Waker` under a key; a
+/// parked thread's waker unparks it. The list is ordered by registration,
+/// and `policy` picks the one a notification wakes.
+#[cfg(feature = "std")]
+pub(crate) struct WaitList {
+    sleepers: AtomicUsize,
+    policy: WakePolicy,
+    lock: Mutex<Wakers>,
+}
+
+#[cfg(feature = "std")]
+struct Wakers {
+    next_key: u64,
+    entries: VecDeque<Waiter>,
+}
+
+#[cfg(feature = "std")]
+struct Waiter {
+    key: u64,
+    priority: u8,
+    waker: Waker,
+}
+
+// Wakes a thread parked in `wait_until_deadline`
+#[cfg(feature = "std")]
+struct ThreadWaker {
+    thread: Thread,
+    woken: AtomicBool,
+}
+
+#[cfg(feature = "std")]
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.woken.store(true, Ordering::Release);
+        self.thread.unpark();
+    }
+}
+
+#[cfg(feature = "std")]
+impl WaitList {
+    pub(crate) fn new() -> Self {
+        Self::with_policy(WakePolicy::Fifo)
+    }
+
+    pub(crate) fn with_policy(policy: WakePolicy) -> Self {
+        Self {
+            sleepers: AtomicUsize::new(0),
+            policy,
+            lock: Mutex::new(Wakers {
+                next_key: 0,
+                entries: VecDeque::new(),
+            }),
+        }
+    }
+
+    pub(crate) fn policy(&self) -> WakePolicy {
+        self.policy
+    }
+
+    /// Calls `attempt` until it returns Some, parking in between.
+    pub(crate) fn wait_until<R>(&self, attempt: impl FnMut() -> Option<R>) -> R {
+        match self.wait_until_deadline(None, attempt) {
+            Some(result) => result,
+            None => unreachable!("waits without a deadline only end on success"),
+        }
+    }
+
+    /// Calls `attempt` until it returns Some or `deadline` passes, parking in
+    /// between. Returns None on timeout.
+    ///
+    /// Spurious or stolen wakeups (another thread took what we were woken
+    /// for) just lead to another attempt; the remaining time is recomputed
+    /// from the deadline on every iteration.
+    pub(crate) fn wait_until_deadline<R>(
+        &self,
+        deadline: Option<Instant>,
+        mut attempt: impl FnMut() -> Option<R>,
+    ) -> Option<R> {
+        let parked = Arc::new(ThreadWaker {
+            thread: thread::current(),
+            woken: AtomicBool::new(false),
+        });
+        let waker = Waker::from(Arc::clone(&parked));
+        let mut key = None;
+        loop {
+            if let Some(result) = attempt() {
+                self.unregister(&mut key);
+                return Some(result);
+            }
+
+            // Register before the final re-check so a concurrent notifier
+            // either wakes us or we see its change
+            self.register(&mut key, &waker);
+            if let Some(result) = attempt() {
+                self.unregister(&mut key);
+                return Some(result);
+            }
+
+            // `park` may return spuriously; only the waker ends the wait
+            while !parked.woken.swap(false, Ordering::Acquire) {
+                match deadline {
+                    None => thread::park(),
+                    Some(deadline) => {
+                        let now = Instant::now();
+                        if now >= deadline {
+                            self.cancel(&mut key);
+                            return None;
+                        }
+                        thread::park_timeout(deadline - now);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Registers (or refreshes) a waker under `key`.
+    ///
+    /// The caller must re-check its condition after this returns and, if it
+    /// can proceed, call `unregister`. A key whose waker was already woken is
+    /// registered again, at the back of the list.
+    pub(crate) fn register(&self, key: &mut Option<u64>, waker: &Waker) {
+        let mut wakers = self.lock.lock().unwrap();
+        if let Some(k) = *key
+            && let Some(existing) = wakers.entries.iter_mut().find(|entry| entry.key == k)
+        {
+            if !existing.waker.will_wake(waker) {
+                existing.waker.clone_from(waker);
+            }
+            return;
+        }
+
+        let k = wakers.next_key;
+        wakers.next_key += 1;
+        self.sleepers.fetch_add(1, Ordering::SeqCst);
+        wakers.entries.push_back(Waiter {
+            key: k,
+            priority: WAKE_PRIORITY.with(Cell::get),
+            waker: waker.clone(),
+        });
+        *key = Some(k);
+        drop(wakers);
+
+        // Pairs with the fence in `notify_one`/`notify_all`, see above
+        fence(Ordering::SeqCst);
+    }
+
+    /// Removes a registration after its waiter made progress.
+    pub(crate) fn unregister(&self, key: &mut Option<u64>) {
+        if let Some(k) = key.take() {
+            let mut wakers = self.lock.lock().unwrap();
+            if let Some(index) = wakers.entries.iter().position(|entry| entry.key == k) {
+                wakers.entries.remove(index);
+                self.sleepers.fetch_sub(1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    /// Removes a registration when its waiter gives up without making
+    /// progress. If its waker was already woken, the wakeup is passed on so
+    /// it is not lost.
+    pub(crate) fn cancel(&self, key: &mut Option<u64>) {
+        if let Some(k) = key.take() {
+            let mut wakers = self.lock.lock().unwrap();
+            match wakers.entries.iter().position(|entry| entry.key == k) {
+                Some(index) => {
+                    wakers.entries.remove(index);
+                    self.sleepers.fetch_sub(1, Ordering::SeqCst);
+                }
+                None => {
+                    drop(wakers);
+                    self.notify_one();
+                }
+            }
+        }
+    }
+
+    /// Wakes one waiting task or parked thread, if any, as `policy` picks.
+    #[inline]
+    pub(crate) fn notify_one(&self) {
+        fence(Ordering::SeqCst);
+        if self.sleepers.load(Ordering::SeqCst) > 0 {
+            let mut wakers = self.lock.lock().unwrap();
+            let entries = &mut wakers.entries;
+            let picked = match self.policy {
+                WakePolicy::Fifo => entries.pop_front(),
+                WakePolicy::Lifo => entries.pop_back(),
+                WakePolicy::Priority => entries
+                    .iter()
+                    .enumerate()
+                    .max_by_key(|&(index, entry)| (entry.priority, Reverse(index)))
+                    .map(|(index, _)| index)
+                    .and_then(|index| entries.remove(index)),
+            };
+            if let Some(waiter) = picked {
+                self.sleepers.fetch_sub(1, Ordering::SeqCst);
+                drop(wakers);
+                waiter.waker.wake();
+            }
+        }
+    }
+
+    /// Wakes every waiting task and parked thread.
+    #[inline]
+    pub(crate) fn notify_all(&self) {
+        fence(Ordering::SeqCst);
+        self.notify_all_fenced();
+    }
+
+    /// `notify_all` for a caller that already issued a SeqCst fence after
+    /// its change, e.g. by notifying another list just before.
+    #[inline]
+    pub(crate) fn notify_all_fenced(&self) {
+        if self.sleepers.load(Ordering::SeqCst) > 0 {
+            let mut wakers = self.lock.lock().unwrap();
+            let woken = std::mem::take(&mut wakers.entries);
+            self.sleepers.fetch_sub(woken.len(), Ordering::SeqCst);
+            drop(wakers);
+            for waiter in woken {
+                waiter.waker.wake();
+            }
+        }
+    }
+}
+
+/// A task's registration on a `WaitList`, for polling an operation that
+/// may have to wait.
+///
+/// Dropping it before the operation completes (e.g. a cancelled future)
+/// removes the registration and passes on a wakeup it already received.
+#[cfg(feature = "std")]
+pub(crate) struct Registration<'a> {
+    list: &'a WaitList,
+    key: Option<u64>,
+}
+
+#[cfg(feature = "std")]
+impl<'a> Registration<'a> {
+    pub(crate) fn new(list: &'a WaitList) -> Self {
+        Self { list, key: None }
+    }
+
+    /// Polls `attempt`, registering the task's waker if it returns None.
+    ///
+    /// The waker is registered before the final attempt, so a change that
+    /// lands in between either shows up in that attempt or wakes the task.
+    pub(crate) fn poll_until<R>(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut attempt: impl FnMut() -> Option<R>,
+    ) -> Poll<R> {
+        if let Some(result) = attempt() {
+            self.list.unregister(&mut self.key);
+            return Poll::Ready(result);
+        }
+
+        self.list.register(&mut self.key, cx.waker());
+        match attempt() {
+            Some(result) => {
+                self.list.unregister(&mut self.key);
+                Poll::Ready(result)
+            }
+            None => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Drop for Registration<'_> {
+    fn drop(&mut self) {
+        self.list.cancel(&mut self.key);
+    }
+}
+
+#[cfg(not(feature = "std"))]
+pub(crate) struct WaitList;
+
+#[cfg(not(feature = "std"))]
+impl WaitList {
+    pub(crate) fn new() -> Self {
+        Self
+    }
+
+    #[inline]
+    pub(crate) fn notify_one(&self) {}
+
+    #[inline]
+    pub(crate) fn notify_all(&self) {}
+}
@@ -0,0 +1,142 @@
+//! Pluggable policies for how a queue operation waits before retrying.
+//!
+//! `send` and `recv` retry when they lose a CAS race or find a slot that
+//! another thread has claimed but not yet finished with. How to spend the
+//! time until the retry is a trade-off, in the style of the LMAX Disruptor's
+//! wait strategies: spinning reacts fastest but burns the core, which under
+//! oversubscription can starve the very thread everyone is waiting on;
+//! yielding or sleeping gives that thread a chance to run.
+
+#[cfg(feature = "std")]
+use std::time::Duration;
+
+/// Decides how to wait before retrying a contended queue operation.
+///
+/// `attempt` counts the retries of the current operation, starting at 0,
+/// so implementations can escalate the longer an operation has been stuck.
+pub trait WaitStrategy: Send + Sync {
+    /// Waits before retry number `attempt`.
+    fn wait(&self, attempt: u32);
+}
+
+/// Always spins. Lowest latency, for threads that each own a core.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BusySpin;
+
+impl WaitStrategy for BusySpin {
+    #[inline]
+    fn wait(&self, _attempt: u32) {
+        core::hint::spin_loop();
+    }
+}
+
+/// Exponential backoff: spins `2^attempt` times, up to `2^ceiling`, then
+/// yields to the scheduler.
+///
+/// Short races are retried almost immediately, while threads that keep
+/// losing back off further each time, easing the cache-line ping-pong on
+/// the contended position. This is what `MpmcQueue::new` uses, with
+/// `DEFAULT_CEILING`. Without `std` there is no scheduler to yield to, and
+/// retries past the ceiling keep spinning `2^ceiling` times.
+#[derive(Debug, Clone, Copy)]
+pub struct Backoff {
+    ceiling: u32,
+}
+
+impl Backoff {
+    /// The default ceiling: at most 64 spins before yielding.
+    pub const DEFAULT_CEILING: u32 = 6;
+
+    /// Largest accepted ceiling; larger values are clamped to it.
+    pub const MAX_CEILING: u32 = 16;
+
+    /// Backoff that spins at most `2^ceiling` times per retry before it
+    /// starts yielding. A ceiling of 0 spins once and then yields.
+    pub const fn with_ceiling(ceiling: u32) -> Self {
+        let ceiling = if ceiling > Self::MAX_CEILING {
+            Self::MAX_CEILING
+        } else {
+            ceiling
+        };
+        Self { ceiling }
+    }
+
+    /// Returns the ceiling, as a power of two of spins.
+    pub const fn ceiling(&self) -> u32 {
+        self.ceiling
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::with_ceiling(Self::DEFAULT_CEILING)
+    }
+}
+
+impl WaitStrategy for Backoff {
+    #[inline]
+    fn wait(&self, attempt: u32) {
+        #[cfg(feature = "std")]
+        if attempt > self.ceiling {
+            std::thread::yield_now();
+            return;
+        }
+        for _ in 0..1u32 << attempt.min(self.ceiling) {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+/// Spins for the first few retries, then yields to the scheduler.
+///
+/// Keeps throughput up when there are more threads than cores: a thread
+/// preempted mid-operation gets to run and finish instead of being spun on.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Yielding;
+
+#[cfg(feature = "std")]
+impl Yielding {
+    const SPIN_LIMIT: u32 = 16;
+}
+
+#[cfg(feature = "std")]
+impl WaitStrategy for Yielding {
+    #[inline]
+    fn wait(&self, attempt: u32) {
+        if attempt < Self::SPIN_LIMIT {
+            core::hint::spin_loop();
+        } else {
+            std::thread::yield_now();
+        }
+    }
+}
+
+/// Spins, then yields, then sleeps for short intervals.
+///
+/// For background work where giving the CPU away matters more than the
+/// microseconds a sleep adds to a stuck operation.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sleeping;
+
+#[cfg(feature = "std")]
+impl Sleeping {
+    const SPIN_LIMIT: u32 = 16;
+    const YIELD_LIMIT: u32 = 64;
+    const SLEEP: Duration = Duration::from_micros(100);
+}
+
+#[cfg(feature = "std")]
+impl WaitStrategy for Sleeping {
+    #[inline]
+    fn wait(&self, attempt: u32) {
+        if attempt < Self::SPIN_LIMIT {
+            core::hint::spin_loop();
+        } else if attempt < Self::YIELD_LIMIT {
+            std::thread::yield_now();
+        } else {
+            std::thread::sleep(Self::SLEEP);
+        }
+    }
+}
@@ -0,0 +1,312 @@
+//! Chase-Lev work-stealing deque.
+
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::ptr;
+use core::sync::atomic::{fence, AtomicPtr, AtomicUsize, Ordering};
+
+/// The owner's end of a work-stealing deque.
+///
+/// Built for task schedulers: each worker thread owns one deque, pushes the
+/// tasks it spawns and pops them back LIFO, which keeps hot data in cache.
+/// Idle workers take tasks from the other end through a `Stealer`, oldest
+/// first. The owner only contends with thieves over the last item, so the
+/// common push and pop touch no shared cache line beyond the deque's own.
+///
+/// The algorithm is Chase and Lev's, with the C11 orderings from Lê et al.,
+/// "Correct and Efficient Work-Stealing for Weak Memory Models" (2013).
+///
+/// The buffer starts at the requested capacity, rounded up to a power of 2,
+/// and doubles whenever a push finds it full, so `push` never fails. It
+/// never shrinks. A thief may still be reading the old buffer when it is
+/// replaced, so old buffers are kept until the deque and all its stealers
+/// are dropped; since each one is half the size of the next, that at most
+/// doubles the memory held.
+///
+/// ```
+/// use mpmc_std::{Steal, WorkStealingDeque};
+///
+/// let mut deque = WorkStealingDeque::new(16);
+/// let stealer = deque.stealer();
+/// deque.push(1);
+/// deque.push(2);
+/// assert_eq!(stealer.steal(), Steal::Success(1));
+/// assert_eq!(deque.pop(), Some(2));
+/// assert_eq!(stealer.steal(), Steal::Empty);
+/// ```
+pub struct WorkStealingDeque<T> {
+    inner: Arc<Inner<T>>,
+}
+
+/// A handle that steals from the far end of a `WorkStealingDeque`.
+///
+/// Created by `WorkStealingDeque::stealer`; clone it for more thieves.
+pub struct Stealer<T> {
+    inner: Arc<Inner<T>>,
+}
+
+/// The outcome of `Stealer::steal`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Steal<T> {
+    /// Took the oldest item.
+    Success(T),
+    /// The deque was empty.
+    Empty,
+    /// Lost a race with the owner or another thief; the deque may still
+    /// have items, so try again (or move on to another victim).
+    Retry,
+}
+
+impl<T> Steal<T> {
+    /// Returns the stolen item, if any.
+    pub fn success(self) -> Option<T> {
+        match self {
+            Steal::Success(item) => Some(item),
+            _ => None,
+        }
+    }
+}
+
+struct Inner<T> {
+    // Next index to steal; only ever increases
+    top: AtomicUsize,
+    // Next index the owner pushes to
+    bottom: AtomicUsize,
+    buffer: AtomicPtr<Buffer<T>>,
+    // Replaced buffers, freed on drop; only touched by the owner. Boxed
+    // because thieves may still hold pointers to the `Buffer` itself
+    #[allow(clippy::vec_box)]
+    retired: UnsafeCell<Vec<Box<Buffer<T>>>>,
+}
+
+struct Buffer<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    mask: usize,
+}
+
+impl<T> Buffer<T> {
+    fn new(capacity: usize) -> Box<Self> {
+        Box::new(Self {
+            slots: (0..capacity).map(|_| UnsafeCell::new(MaybeUninit::uninit())).collect(),
+            mask: capacity - 1,
+        })
+    }
+
+    fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn slot(&self, index: usize) -> *mut MaybeUninit<T> {
+        self.slots[index & self.mask].get()
+    }
+}
+
+// Items between `top` and `bottom`; negative while the owner's pop has
+// reserved an item that a thief may also be taking
+fn size(bottom: usize, top: usize) -> isize {
+    bottom.wrapping_sub(top) as isize
+}
+
+impl<T: Send> WorkStealingDeque<T> {
+    /// Creates an empty deque whose buffer starts with room for `capacity`
+    /// items, rounded up to a power of 2.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "Capacity must be greater than 0");
+
+        let buffer = Buffer::new(capacity.next_power_of_two());
+        Self {
+            inner: Arc::new(Inner {
+                top: AtomicUsize::new(0),
+                bottom: AtomicUsize::new(0),
+                buffer: AtomicPtr::new(Box::into_raw(buffer)),
+                retired: UnsafeCell::new(Vec::new()),
+            }),
+        }
+    }
+
+    /// Creates a handle that steals from this deque.
+    pub fn stealer(&self) -> Stealer<T> {
+        Stealer {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+
+    /// Pushes an item onto the owner's end, growing the buffer if it is full.
+    pub fn push(&mut self, item: T) {
+        let inner = &*self.inner;
+        let bottom = inner.bottom.load(Ordering::Relaxed);
+        let top = inner.top.load(Ordering::Acquire);
+        let mut buffer = inner.buffer.load(Ordering::Relaxed);
+
+        if size(bottom, top) >= unsafe { (*buffer).capacity() } as isize {
+            buffer = self.grow(top, bottom);
+        }
+        unsafe {
+            (*buffer).slot(bottom).write(MaybeUninit::new(item));
+        }
+        // Release: a thief that sees the new bottom also sees the item
+        fence(Ordering::Release);
+        inner.bottom.store(bottom.wrapping_add(1), Ordering::Relaxed);
+    }
+
+    /// Pops the most recently pushed item, or returns None if the deque is
+    /// empty.
+    pub fn pop(&mut self) -> Option<T> {
+        let inner = &*self.inner;
+        let bottom = inner.bottom.load(Ordering::Relaxed).wrapping_sub(1);
+        let buffer = inner.buffer.load(Ordering::Relaxed);
+        // Reserve the last item before looking at top. SeqCst: thieves
+        // either see the lowered bottom, or this load sees their new top,
+        // so an item is never handed out twice
+        inner.bottom.store(bottom, Ordering::Relaxed);
+        fence(Ordering::SeqCst);
+        let top = inner.top.load(Ordering::Relaxed);
+
+        let size = size(bottom, top);
+        if size < 0 {
+            inner.bottom.store(bottom.wrapping_add(1), Ordering::Relaxed);
+            return None;
+        }
+        if size > 0 {
+            // More than one item: no thief can reach this one
+            return Some(unsafe { (*buffer).slot(bottom).read().assume_init() });
+        }
+
+        // The last item: race thieves for it by advancing top ourselves
+        let won = inner
+            .top
+            .compare_exchange(top, top.wrapping_add(1), Ordering::SeqCst, Ordering::Relaxed)
+            .is_ok();
+        inner.bottom.store(bottom.wrapping_add(1), Ordering::Relaxed);
+        won.then(|| unsafe { (*buffer).slot(bottom).read().assume_init() })
+    }
+
+    /// Returns the approximate number of items in the deque.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if the deque is empty.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns how many items fit before the next push grows the buffer.
+    pub fn capacity(&self) -> usize {
+        unsafe { (*self.inner.buffer.load(Ordering::Relaxed)).capacity() }
+    }
+
+    // Moves the live items into a buffer twice the size and publishes it
+    fn grow(&self, top: usize, bottom: usize) -> *mut Buffer<T> {
+        let inner = &*self.inner;
+        let old = inner.buffer.load(Ordering::Relaxed);
+        let new = Buffer::new(unsafe { (*old).capacity() } * 2);
+
+        // A bitwise copy: whoever later takes index i owns the item, read
+        // from whichever buffer they loaded, so it is never dropped twice
+        let mut i = top;
+        while i != bottom {
+            unsafe {
+                ptr::copy_nonoverlapping((*old).slot(i), new.slot(i), 1);
+            }
+            i = i.wrapping_add(1);
+        }
+
+        let new = Box::into_raw(new);
+        // Release: thieves that load the new buffer see the copied items
+        inner.buffer.store(new, Ordering::Release);
+        // Thieves may still hold the old pointer, so keep it until drop.
+        // Only `push`, which takes `&mut self`, reaches here, so this is
+        // the sole access to `retired`
+        unsafe {
+            (*inner.retired.get()).push(Box::from_raw(old));
+        }
+        new
+    }
+}
+
+impl<T: Send> Stealer<T> {
+    /// Attempts to take the oldest item.
+    ///
+    /// Returns `Steal::Retry` if the owner or another thief took that item
+    /// first.
+    pub fn steal(&self) -> Steal<T> {
+        let inner = &*self.inner;
+        let top = inner.top.load(Ordering::Acquire);
+        // SeqCst: pairs with the fence in `pop`, see there
+        fence(Ordering::SeqCst);
+        let bottom = inner.bottom.load(Ordering::Acquire);
+        if size(bottom, top) <= 0 {
+            return Steal::Empty;
+        }
+
+        // Read before claiming: once top moves the owner may overwrite the
+        // slot. The copy stays uninit, and is forgotten, unless the
+        // exchange proves the item was still ours to take
+        let buffer = inner.buffer.load(Ordering::Acquire);
+        let item = unsafe { ptr::read_volatile((*buffer).slot(top)) };
+        if inner
+            .top
+            .compare_exchange(top, top.wrapping_add(1), Ordering::SeqCst, Ordering::Relaxed)
+            .is_err()
+        {
+            return Steal::Retry;
+        }
+        Steal::Success(unsafe { item.assume_init() })
+    }
+
+    /// Returns the approximate number of items in the deque.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    /// Returns true if the deque is empty.
+    ///
+    /// Note: This is a snapshot view and may change immediately after the call.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Clone for Stealer<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> Inner<T> {
+    fn len(&self) -> usize {
+        let top = self.top.load(Ordering::Acquire);
+        let bottom = self.bottom.load(Ordering::Acquire);
+        size(bottom, top).max(0) as usize
+    }
+}
+
+unsafe impl<T: Send> Send for Inner<T> {}
+unsafe impl<T: Send> Sync for Inner<T> {}
+
+impl<T> Drop for Inner<T> {
+    fn drop(&mut self) {
+        let buffer = unsafe { Box::from_raw(*self.buffer.get_mut()) };
+        let bottom = *self.bottom.get_mut();
+        let mut i = *self.top.get_mut();
+        while i != bottom {
+            unsafe {
+                (*buffer.slot(i)).assume_init_drop();
+            }
+            i = i.wrapping_add(1);
+        }
+        // Retired buffers hold only stale copies, so just free them
+    }
+}